@@ -0,0 +1,213 @@
+//!
+//! apple_build.rs
+//!
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// An Apple build number such as `20C5049e`.
+///
+/// Builds are ordered by train and letter, then beta seeds before other builds, then by number and
+/// suffix. So `20C5049e < 20C65`, and a re-spin like `22A380a` sorts after `22A380`. Builds from
+/// different products of the same train (iOS and iPadOS) compare directly.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::AppleBuild;
+///
+/// let build: AppleBuild = "20C5049e".parse().unwrap();
+/// assert_eq!(build.train, 20);
+/// assert_eq!(build.letter, 'C');
+/// assert_eq!(build.number, 5049);
+/// assert_eq!(build.suffix, Some('e'));
+/// assert!(build.is_beta());
+///
+/// let release: AppleBuild = "20C65".parse().unwrap();
+/// assert!(!release.is_beta());
+/// assert!(build < release);
+/// ```
+///
+/// Serializes as its display string, e.g. `"20C5049e"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AppleBuild {
+    /// Major train, usually tracking the OS major version, e.g. `20` for iOS 16.
+    pub train: u32,
+
+    /// Minor letter, advancing with each minor OS version, e.g. `C` for x.2.
+    pub letter: char,
+
+    /// Build number within the train. Beta seeds are numbered 5000 and up.
+    pub number: u32,
+
+    /// Trailing lowercase letter, present on beta seeds and re-spins.
+    pub suffix: Option<char>,
+}
+
+impl AppleBuild {
+    /// Returns `true` if this is a beta seed: a 5xxx build number with a trailing letter.
+    pub fn is_beta(&self) -> bool {
+        (5000..6000).contains(&self.number) && self.suffix.is_some()
+    }
+
+    /// Returns `true` if `other` is a different build of the same train and letter, i.e. a re-spin
+    /// of the same minor version.
+    pub fn is_respin_of(&self, other: &AppleBuild) -> bool {
+        self.train == other.train && self.letter == other.letter && self != other
+    }
+
+    /// Sort key: beta seeds are built before the release builds of the same train and letter.
+    fn sort_key(&self) -> (u32, char, bool, u32, Option<char>) {
+        (
+            self.train,
+            self.letter,
+            !self.is_beta(),
+            self.number,
+            self.suffix,
+        )
+    }
+}
+
+impl Ord for AppleBuild {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for AppleBuild {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for AppleBuild {
+    type Err = Error;
+
+    fn from_str(build: &str) -> Result<Self> {
+        let invalid = |message: &str| Error::Version {
+            value: build.to_string(),
+            message: message.to_string(),
+        };
+
+        let letter_index = build
+            .find(|c: char| c.is_ascii_uppercase())
+            .ok_or_else(|| invalid("missing build letter"))?;
+        let (train, rest) = build.split_at(letter_index);
+        let train = train.parse().map_err(|_| invalid("invalid build train"))?;
+
+        let mut chars = rest.chars();
+        let letter = chars
+            .next()
+            .ok_or_else(|| invalid("missing build letter"))?;
+        let rest = chars.as_str();
+
+        let (number, suffix) = match rest.char_indices().last() {
+            Some((index, c)) if c.is_ascii_lowercase() => (&rest[..index], Some(c)),
+            _ => (rest, None),
+        };
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid("invalid build number"));
+        }
+        let number = number
+            .parse()
+            .map_err(|_| invalid("invalid build number"))?;
+
+        Ok(AppleBuild {
+            train,
+            letter,
+            number,
+            suffix,
+        })
+    }
+}
+
+impl Display for AppleBuild {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}{}{}", self.train, self.letter, self.number)?;
+        if let Some(suffix) = self.suffix {
+            write!(formatter, "{}", suffix)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for AppleBuild {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AppleBuild {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_parse_build() {
+    let build: AppleBuild = "14A5294e".parse().unwrap();
+    assert_eq!(
+        build,
+        AppleBuild {
+            train: 14,
+            letter: 'A',
+            number: 5294,
+            suffix: Some('e'),
+        }
+    );
+    assert_eq!(build.to_string(), "14A5294e");
+
+    let build: AppleBuild = "20B110".parse().unwrap();
+    assert_eq!(build.suffix, None);
+    assert!(!build.is_beta());
+    assert_eq!(build.to_string(), "20B110");
+
+    // Release builds may also carry a suffix
+    let build: AppleBuild = "14B47b".parse().unwrap();
+    assert_eq!(build.suffix, Some('b'));
+    assert!(!build.is_beta());
+}
+
+#[test]
+fn test_parse_invalid_build() {
+    for build in ["", "20", "C5049e", "20C", "20Ce", "20C50x9e", "20c5049"] {
+        assert!(build.parse::<AppleBuild>().is_err(), "{}", build);
+    }
+}
+
+#[test]
+fn test_build_ordering() {
+    let mut builds: Vec<AppleBuild> = [
+        "22A380a", "20C65", "22A380", "20C5049e", "20B110", "20C5058d",
+    ]
+    .iter()
+    .map(|build| build.parse().unwrap())
+    .collect();
+    builds.sort();
+
+    let builds: Vec<String> = builds.iter().map(AppleBuild::to_string).collect();
+    assert_eq!(
+        builds,
+        vec![
+            "20B110", "20C5049e", "20C5058d", "20C65", "22A380", "22A380a"
+        ]
+    );
+}
+
+#[test]
+fn test_build_respin() {
+    let build: AppleBuild = "22A380".parse().unwrap();
+    let respin: AppleBuild = "22A380a".parse().unwrap();
+    let next: AppleBuild = "22B74".parse().unwrap();
+
+    assert!(respin.is_respin_of(&build));
+    assert!(!build.is_respin_of(&build));
+    assert!(!next.is_respin_of(&build));
+}
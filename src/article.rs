@@ -2,61 +2,157 @@
 //! article.rs
 //!
 
+use crate::body::Body;
+use crate::link::Link;
 use crate::software_release::SoftwareRelease;
 use chrono::NaiveDate;
-use semver::{BuildMetadata, Prerelease};
+use semver::BuildMetadata;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use url::Url;
 
 /// An article from the Apple Developer software releases site.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::{Article, SoftwareRelease};
+/// use chrono::NaiveDate;
+///
+/// let title = "iOS 16.1.2 (20B110)".to_string();
+/// let article = Article {
+///     software_release: SoftwareRelease::software_release(title.clone()),
+///     title,
+///     date: NaiveDate::from_ymd_opt(2022, 11, 30).unwrap(),
+///     release_notes_url: None,
+///     permalink: None,
+///     links: Vec::new(),
+///     title_html: None,
+///     body: None,
+/// };
+/// assert_eq!(article.to_string(), "2022-11-30 - iOS 16.1.2 (20B110)");
+/// ```
+///
+/// # JSON
+///
+/// ```json
+/// {
+///   "title": "iOS 16.2 beta 3 (20C5049e)",
+///   "software_release": { "product": "iOS", "version": { "major": 16, ... } },
+///   "date": "2022-11-15",
+///   "release_notes_url": "https://developer.apple.com/go/?id=ios-16.2-rn",
+///   "permalink": "https://developer.apple.com/news/releases/?id=11152022a",
+///   "links": [{ "kind": "ReleaseNotes", "text": "View release notes", "url": "..." }],
+///   "body": { "text": "View release notes", "markdown": "[View release notes](...)" }
+/// }
+/// ```
+///
+/// `software_release`, `release_notes_url`, `permalink` and `body` are `null` when absent. See
+/// [`SoftwareRelease`] for the shape of `software_release`, [`Link`] for the entries of `links` and
+/// [`Body`] for `body`. `title_html` is only present when the title had markup or entities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Article {
     /// The article title. For an OS release, this is the OS version.
     /// Example: iOS 16.2 beta 3 (20C5049e)
     pub title: String,
 
+    /// The title as it appeared in the page, when that differs from `title`, e.g.
+    /// `Safari&nbsp;17 <span>beta</span>`. Kept for debugging.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_html: Option<String>,
+
     /// Struct representing the product and version.
     pub software_release: Option<SoftwareRelease>,
 
     /// The article date. Example: November 15, 2022
     pub date: NaiveDate,
 
-    /// The release notes URL. This will be `None` if the article does not have release notes.
+    /// The release notes URL, from the first release notes link in `links` on the same host as the
+    /// page the article was read from.
+    /// This will be `None` if the article does not have release notes.
     /// Release note URLs end in a path like `/go/?id=ios-16.2-rn`
     pub release_notes_url: Option<Url>,
+
+    /// Link to the article itself, e.g. `https://developer.apple.com/news/releases/?id=11282023a`.
+    #[serde(default)]
+    pub permalink: Option<Url>,
+
+    /// Links from the article body, in order.
+    #[serde(default)]
+    pub links: Vec<Link>,
+
+    /// The descriptive text of the article, if it has any.
+    #[serde(default)]
+    pub body: Option<Body>,
+}
+
+impl Article {
+    /// Apple's identifier for the article, the `id` of its permalink, e.g. `08082022a`.
+    pub fn id(&self) -> Option<String> {
+        self.permalink.as_ref().and_then(|permalink| {
+            permalink
+                .query_pairs()
+                .find(|(name, _)| name == "id")
+                .map(|(_, id)| id.into_owned())
+                .filter(|id| !id.is_empty())
+        })
+    }
+
+    /// Identifies the article across pages and runs: its [`id`](Article::id), or its date and title
+    /// if it has no permalink.
+    pub fn key(&self) -> String {
+        self.id().unwrap_or_else(|| self.legacy_key())
+    }
+
+    /// The date and title key which identified articles from the releases page before their
+    /// permalinks were parsed. Used to recognize articles in state saved by older versions.
+    pub(crate) fn legacy_key(&self) -> String {
+        format!("{} {}", self.date, self.title)
+    }
 }
 
 impl Display for Article {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
         write!(formatter, "{} - ", self.date.format("%Y-%m-%d"))?;
 
-        if let Some(SoftwareRelease { product, version }) = &self.software_release {
+        if let Some(SoftwareRelease {
+            product,
+            marketing_name,
+            version,
+            stage,
+            ..
+        }) = &self.software_release
+        {
             write!(formatter, "{} ", product)?;
+            if let Some(marketing_name) = marketing_name {
+                write!(formatter, "{} ", marketing_name)?;
+            }
 
             // Write semver without trailing zeros
-            write!(formatter, "{}", version.major.to_string())?;
+            write!(formatter, "{}", version.major)?;
             // Include minor if we have a patch version
             if version.minor > 0 || version.patch > 0 {
-                write!(formatter, ".{}", version.minor.to_string())?;
+                write!(formatter, ".{}", version.minor)?;
             }
             if version.patch > 0 {
-                write!(formatter, ".{}", version.patch.to_string())?;
+                write!(formatter, ".{}", version.patch)?;
             }
 
-            if version.pre != Prerelease::EMPTY {
-                write!(formatter, " {}", version.pre.to_string().replace("-", " "))?;
+            if stage.is_prerelease() {
+                write!(formatter, " {}", stage)?;
             }
             if version.build != BuildMetadata::EMPTY {
-                write!(formatter, " ({})", version.build.to_string())?;
+                write!(formatter, " ({})", version.build)?;
             }
 
             if let Some(url) = &self.release_notes_url {
-                write!(formatter, " - {}", url.to_string())?;
+                write!(formatter, " - {}", url)?;
             }
 
             // Suppresses error: Type mismatch [E0308] expected `core::fmt::Result`, but found `()`
             write!(formatter, "")
         } else {
-            write!(formatter, "{} - {}", self.date.format("%Y-%m-%d"), self.title)
+            write!(formatter, "{}", self.title)
         }
     }
 }
@@ -72,6 +168,10 @@ fn test_article_display() {
         software_release: None,
         date,
         release_notes_url: Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").ok(),
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
     };
 
     assert_eq!(
@@ -91,11 +191,35 @@ fn test_article_display_software_release() {
         software_release: Some(release),
         date,
         release_notes_url: Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").ok(),
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
     };
 
     assert_eq!(
         article.to_string(),
-        "2022-11-15 - iOS 16.2 beta 3 (20C5049e)"
+        "2022-11-15 - iOS 16.2 beta 3 (20C5049e) - https://developer.apple.com/go/?id=ios-16.2-rn"
+    );
+}
+
+#[test]
+fn test_article_display_marketing_name() {
+    let title = "macOS Sonoma 14.2 RC 2 (23C64)";
+    let article = Article {
+        title: title.to_string(),
+        software_release: SoftwareRelease::software_release(title.to_string()),
+        date: NaiveDate::from_ymd_opt(2023, 12, 5).unwrap(),
+        release_notes_url: None,
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    };
+
+    assert_eq!(
+        article.to_string(),
+        "2023-12-05 - macOS Sonoma 14.2 RC 2 (23C64)"
     );
 }
 
@@ -107,7 +231,82 @@ fn test_article_display_without_url() {
         software_release: None,
         date,
         release_notes_url: None,
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
     };
 
     assert_eq!(article.to_string(), "2022-11-15 - App Store Connect 1.11");
 }
+
+#[test]
+fn test_article_json_round_trip() {
+    let title = "iOS 16.2 beta 3 (20C5049e)";
+    let article = Article {
+        title: title.to_string(),
+        software_release: SoftwareRelease::software_release(title.to_string()),
+        date: NaiveDate::from_ymd_opt(2022, 11, 15).unwrap(),
+        release_notes_url: Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").ok(),
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    };
+
+    let json = serde_json::to_value(&article).unwrap();
+    assert_eq!(json["title"], title);
+    assert_eq!(json["date"], "2022-11-15");
+    assert_eq!(json["software_release"]["product"], "iOS");
+    assert_eq!(
+        json["release_notes_url"],
+        "https://developer.apple.com/go/?id=ios-16.2-rn"
+    );
+
+    let decoded: Article = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, article);
+}
+
+#[test]
+fn test_article_json_nulls() {
+    let article = Article {
+        title: "App Store Connect 1.11".to_string(),
+        software_release: None,
+        date: NaiveDate::from_ymd_opt(2022, 11, 15).unwrap(),
+        release_notes_url: None,
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    };
+
+    let json = serde_json::to_value(&article).unwrap();
+    assert_eq!(json["software_release"], serde_json::Value::Null);
+    assert_eq!(json["release_notes_url"], serde_json::Value::Null);
+
+    let decoded: Article = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, article);
+}
+
+#[test]
+fn test_article_key() {
+    let mut article = Article {
+        title: "iOS 16.2 (20C65)".to_string(),
+        software_release: None,
+        date: NaiveDate::from_ymd_opt(2022, 12, 13).unwrap(),
+        release_notes_url: None,
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    };
+    assert_eq!(article.id(), None);
+    assert_eq!(article.key(), "2022-12-13 iOS 16.2 (20C65)");
+
+    article.permalink = Url::parse("https://developer.apple.com/news/releases/").ok();
+    assert_eq!(article.id(), None);
+
+    article.permalink = Url::parse("https://developer.apple.com/news/releases/?id=12132022a").ok();
+    assert_eq!(article.id().as_deref(), Some("12132022a"));
+    assert_eq!(article.key(), "12132022a");
+}
@@ -0,0 +1,138 @@
+//!
+//! async_fetcher.rs
+//!
+//! Available with the `async` feature.
+//!
+
+use crate::APP_USER_AGENT;
+use crate::config::HttpConfig;
+use crate::error::Result;
+use crate::fetcher::{backoff_delay, is_transient, root_certificates};
+use reqwest::Response;
+use reqwest::header::HeaderMap;
+use std::time::Duration;
+
+/// Fetches pages over HTTP with the async `reqwest` client, for use on a tokio runtime.
+///
+/// Retries transient failures the same way as [`HttpFetcher`](crate::fetcher::HttpFetcher).
+/// Clones share the underlying connection pool, so one fetcher can serve concurrent requests.
+///
+/// # Example
+///
+/// ```no_run
+/// use apple_releases::APPLE_DEV_RELEASES;
+/// use apple_releases::async_fetcher::AsyncHttpFetcher;
+///
+/// # async fn run() -> apple_releases::Result<()> {
+/// let fetcher = AsyncHttpFetcher::new()?;
+/// let (page, notes) = tokio::join!(
+///     fetcher.fetch(APPLE_DEV_RELEASES),
+///     fetcher.fetch("https://developer.apple.com/go/?id=xcode-14-sdk-rn"),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncHttpFetcher {
+    client: reqwest::Client,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl AsyncHttpFetcher {
+    /// Creates a fetcher with the crate's user agent and default settings.
+    pub fn new() -> Result<Self> {
+        Self::with_config(&HttpConfig::default())
+    }
+
+    /// Creates a fetcher with the crate's user agent and the given settings.
+    ///
+    /// # Arguments
+    ///
+    /// - `config` - Timeouts, retries, proxy and extra root certificates.
+    pub fn with_config(config: &HttpConfig) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.timeout);
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        for certificate in root_certificates(config)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+            retries: config.retries,
+            backoff: config.backoff,
+        })
+    }
+
+    /// Returns the body of the page at `url`. Error statuses are returned as `Error::Network`.
+    pub async fn fetch(&self, url: &str) -> Result<String> {
+        let response = self.send(url, HeaderMap::new()).await?;
+        Ok(response.error_for_status()?.text().await?)
+    }
+
+    /// Sends a GET request, retrying transient failures.
+    async fn send(&self, url: &str, headers: HeaderMap) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let result = self.client.get(url).headers(headers.clone()).send().await;
+            let transient = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(error) => is_transient(error),
+            };
+            if !transient || attempt >= self.retries {
+                return Ok(result?);
+            }
+
+            tokio::time::sleep(backoff_delay(self.backoff, attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn retrying_fetcher(retries: u32) -> AsyncHttpFetcher {
+    AsyncHttpFetcher::with_config(&HttpConfig {
+        retries,
+        backoff: Duration::ZERO,
+        ..HttpConfig::default()
+    })
+    .unwrap()
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_async_fetcher() {
+    use crate::fetcher::{http_response, serve};
+
+    let (url, server) = serve(vec![
+        http_response("503 Service Unavailable", &[], ""),
+        http_response("200 OK", &[], "<html></html>"),
+    ]);
+
+    let body = retrying_fetcher(1).fetch(&url).await.unwrap();
+    assert_eq!(body, "<html></html>");
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].contains(APP_USER_AGENT));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_async_fetcher_error_status() {
+    use crate::error::Error;
+    use crate::fetcher::{http_response, serve};
+
+    let (url, server) = serve(vec![http_response("404 Not Found", &[], "")]);
+
+    let result = retrying_fetcher(3).fetch(&url).await;
+    assert!(matches!(result, Err(Error::Network(_))));
+    assert_eq!(server.join().unwrap().len(), 1);
+}
@@ -0,0 +1,307 @@
+//!
+//! body.rs
+//!
+
+use scraper::{ElementRef, Node};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The descriptive text of an article, such as an App Store Connect announcement.
+///
+/// # JSON
+///
+/// ```json
+/// {
+///   "text": "You can now view sales by region.\n\n- View downloads",
+///   "markdown": "You can now view **sales** by region.\n\n- [View downloads](https://developer.apple.com/download/)"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Body {
+    /// Plain text. Paragraphs are separated by blank lines and list items start with `- ` or a
+    /// number.
+    pub text: String,
+
+    /// Markdown with links, emphasis, code and lists kept. Other markup is dropped, and only
+    /// `http`, `https` and `mailto` links are kept, so it is safe to render.
+    pub markdown: String,
+}
+
+/// Renders the contents of `element` as a [`Body`], or `None` if it has no text. Relative links
+/// are resolved against `base`, the URL of the page the body was found on.
+pub(crate) fn from_html(element: &ElementRef, base: &Url) -> Option<Body> {
+    let text = Renderer::render(element, None);
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(Body {
+        text,
+        markdown: Renderer::render(element, Some(base)),
+    })
+}
+
+/// A paragraph or list item of rendered output.
+struct Block {
+    text: String,
+    list_item: bool,
+}
+
+/// Walks an HTML tree, collecting blocks of text with or without Markdown markup.
+struct Renderer<'a> {
+    markdown: bool,
+
+    /// URL which relative links are resolved against, for Markdown.
+    base: Option<&'a Url>,
+
+    blocks: Vec<Block>,
+
+    /// Inline text not yet ended by a block.
+    line: String,
+
+    /// Item counts of the enclosing lists, `None` for bulleted lists.
+    lists: Vec<Option<usize>>,
+
+    /// The marker of a list item whose first block has not been written.
+    marker: Option<String>,
+}
+
+impl<'a> Renderer<'a> {
+    /// Renders `element` as plain text, or as Markdown if `base` is given.
+    fn render(element: &ElementRef, base: Option<&'a Url>) -> String {
+        let mut renderer = Renderer {
+            markdown: base.is_some(),
+            base,
+            blocks: Vec::new(),
+            line: String::new(),
+            lists: Vec::new(),
+            marker: None,
+        };
+        renderer.children(element);
+        renderer.end_block();
+
+        let mut output = String::new();
+        let mut previous_item = false;
+        for block in renderer.blocks {
+            if !output.is_empty() {
+                output.push_str(if previous_item && block.list_item {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            output.push_str(&block.text);
+            previous_item = block.list_item;
+        }
+        output
+    }
+
+    fn children(&mut self, element: &ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(&child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: &ElementRef) {
+        match element.value().name() {
+            "script" | "style" | "template" | "button" | "svg" => {}
+            "br" => {
+                self.line.truncate(self.line.trim_end_matches(' ').len());
+                self.line
+                    .push_str(if self.markdown { "\\\n" } else { "\n" });
+            }
+            "ul" | "ol" => {
+                self.end_block();
+                let numbered = element.value().name() == "ol";
+                self.lists.push(numbered.then_some(0));
+                self.children(element);
+                self.lists.pop();
+                self.end_block();
+            }
+            "li" => {
+                self.end_block();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(count)) => {
+                        *count += 1;
+                        format!("{}. ", count)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.marker = Some(format!("{}{}", "  ".repeat(depth), marker));
+                self.children(element);
+                self.end_block();
+                self.marker = None;
+            }
+            "p" | "div" | "section" | "article" | "blockquote" | "il" | "table" | "tr" | "h1"
+            | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.end_block();
+                self.children(element);
+                self.end_block();
+            }
+            "a" if let Some(base) = self.base => match link_url(element, base) {
+                Some(url) => self.wrap(element, "[", &format!("]({})", url)),
+                None => self.children(element),
+            },
+            "strong" | "b" if self.markdown => self.wrap(element, "**", "**"),
+            "em" | "i" if self.markdown => self.wrap(element, "*", "*"),
+            "code" if self.markdown => self.wrap(element, "`", "`"),
+            _ => self.children(element),
+        }
+    }
+
+    /// Appends text with runs of whitespace collapsed, escaped for Markdown if needed.
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !self.line.is_empty() && !self.line.ends_with([' ', '\n']) {
+                    self.line.push(' ');
+                }
+            } else {
+                if self.markdown && "\\`*_[]<>".contains(c) {
+                    self.line.push('\\');
+                }
+                self.line.push(c);
+            }
+        }
+    }
+
+    /// Renders the children of `element` between `open` and `close`, keeping surrounding spaces
+    /// outside the markup. Elements without text are dropped.
+    fn wrap(&mut self, element: &ElementRef, open: &str, close: &str) {
+        let start = self.line.len();
+        self.children(element);
+        let content = self.line.split_off(start);
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            self.line.push_str(&content);
+            return;
+        }
+
+        if content.starts_with(' ') && !self.line.is_empty() {
+            self.line.push(' ');
+        }
+        self.line.push_str(open);
+        self.line.push_str(trimmed);
+        self.line.push_str(close);
+        if content.ends_with(' ') {
+            self.line.push(' ');
+        }
+    }
+
+    /// Ends the current paragraph or list item, if it has any text.
+    fn end_block(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        let lines: Vec<&str> = line
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+
+        let in_list = !self.lists.is_empty();
+        let (first, rest) = match self.marker.take() {
+            Some(marker) => {
+                let indent = " ".repeat(marker.len());
+                (marker, indent)
+            }
+            None if in_list => {
+                let indent = "  ".repeat(self.lists.len());
+                (indent.clone(), indent)
+            }
+            None => (String::new(), String::new()),
+        };
+
+        let text = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let prefix = if index == 0 { &first } else { &rest };
+                format!("{}{}", prefix, line)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.blocks.push(Block {
+            text,
+            list_item: in_list,
+        });
+    }
+}
+
+/// Returns the absolute URL of a link for Markdown, or `None` if it is missing or not a web or
+/// mail link.
+fn link_url(element: &ElementRef, base: &Url) -> Option<String> {
+    let href = element.value().attr("href")?.trim();
+    let url = crate::url::resolve(base, href).ok()?;
+    matches!(url.scheme(), "http" | "https" | "mailto")
+        .then(|| url.as_str().replace('(', "%28").replace(')', "%29"))
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn render(html: &str) -> Option<Body> {
+    let fragment = scraper::Html::parse_fragment(html);
+    from_html(&fragment.root_element(), &crate::RELEASES_PAGE)
+}
+
+#[test]
+fn test_body() {
+    let body = render(
+        r#"
+        <p>You can now view <strong>sales</strong> in
+           App Store Connect.<br>See <a href="/help/sales">the guide</a> for details.</p>
+        <p><a href="javascript:alert(1)">Don't run</a> this <script>alert(2)</script>*now*</p>
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        body.text,
+        "You can now view sales in App Store Connect.\nSee the guide for details.\n\n\
+         Don't run this *now*"
+    );
+    assert_eq!(
+        body.markdown,
+        "You can now view **sales** in App Store Connect.\\\n\
+         See [the guide](https://developer.apple.com/help/sales) for details.\n\n\
+         Don't run this \\*now\\*"
+    );
+}
+
+#[test]
+fn test_body_lists() {
+    let body = render(
+        r#"
+        <p>Changes:</p>
+        <ul>
+            <li>Faster builds</li>
+            <li>New <em>simulators</em>
+                <ol><li>iPhone</li><li>iPad</li></ol>
+            </li>
+        </ul>
+        <p>Thanks!</p>
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        body.markdown,
+        "Changes:\n\n- Faster builds\n- New *simulators*\n  1. iPhone\n  2. iPad\n\nThanks!"
+    );
+}
+
+#[test]
+fn test_body_empty() {
+    assert_eq!(render("<p> </p><button>Share</button>"), None);
+}
@@ -0,0 +1,406 @@
+//!
+//! cache.rs
+//!
+
+use crate::error::{Error, Result};
+use crate::fetcher::{Fetcher, HttpFetcher};
+use reqwest::StatusCode;
+use reqwest::header::{
+    CACHE_CONTROL, ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How a [`CachingFetcher`] uses its cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum CacheMode {
+    /// Serve fresh entries from the cache and revalidate stale ones.
+    #[default]
+    Normal,
+
+    /// Ignore cached entries and download again, updating the cache.
+    Refresh,
+}
+
+/// Metadata stored alongside a cached body.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// The URL the body was fetched from.
+    url: String,
+
+    /// `ETag` response header, sent back as `If-None-Match`.
+    etag: Option<String>,
+
+    /// `Last-Modified` response header, sent back as `If-Modified-Since`.
+    last_modified: Option<String>,
+
+    /// When the body was last fetched or revalidated, in seconds since the Unix epoch.
+    fetched_at: u64,
+
+    /// `Cache-Control: max-age`, in seconds. Zero means always revalidate.
+    max_age: u64,
+
+    /// FNV-1a hash of the body, so a body left from another write is not served with this entry.
+    body_hash: u64,
+}
+
+impl CacheEntry {
+    /// Returns `true` if the entry can be used without revalidating.
+    fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < self.max_age
+    }
+
+    /// Updates validators and freshness from response headers.
+    fn update(&mut self, headers: &HeaderMap, now: u64) {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+
+        if let Some(etag) = header(ETAG) {
+            self.etag = Some(etag);
+        }
+        if let Some(last_modified) = header(LAST_MODIFIED) {
+            self.last_modified = Some(last_modified);
+        }
+        self.fetched_at = now;
+        self.max_age = max_age(headers);
+    }
+}
+
+/// Fetches pages over HTTP, storing them on disk and revalidating with `ETag` and `Last-Modified`.
+///
+/// Each URL is stored as a body file and a JSON metadata file named after a hash of the URL. The
+/// metadata records a hash of the body, and is written after it, so an entry whose write was
+/// interrupted is downloaded again rather than served with the wrong body.
+/// Responses are served from the cache without a request while younger than their
+/// `Cache-Control: max-age`. Responses marked `no-store` are not cached.
+///
+/// # Example
+///
+/// ```no_run
+/// use apple_releases::APPLE_DEV_RELEASES;
+/// use apple_releases::cache::{CacheMode, CachingFetcher};
+/// use apple_releases::fetcher::Fetcher;
+///
+/// let fetcher = CachingFetcher::new(CachingFetcher::default_dir().unwrap(), CacheMode::Normal).unwrap();
+/// let body = fetcher.fetch(APPLE_DEV_RELEASES).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachingFetcher {
+    http: HttpFetcher,
+    dir: PathBuf,
+    mode: CacheMode,
+}
+
+impl CachingFetcher {
+    /// Creates a fetcher which caches pages in `dir`.
+    ///
+    /// # Arguments
+    ///
+    /// - `dir` - The cache directory. Created on first write.
+    /// - `mode` - How the cache is used.
+    pub fn new(dir: impl Into<PathBuf>, mode: CacheMode) -> Result<Self> {
+        Ok(Self::with_http(HttpFetcher::new()?, dir, mode))
+    }
+
+    /// Creates a fetcher which caches pages in `dir`, making requests with `http`.
+    ///
+    /// # Arguments
+    ///
+    /// - `http` - The fetcher used for requests, e.g. one with custom timeouts.
+    /// - `dir` - The cache directory. Created on first write.
+    /// - `mode` - How the cache is used.
+    pub fn with_http(http: HttpFetcher, dir: impl Into<PathBuf>, mode: CacheMode) -> Self {
+        Self {
+            http,
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    /// Returns the default cache directory, `$XDG_CACHE_HOME/apple_releases` on Linux.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")))
+    }
+
+    /// Path of the body file for `url`, with `extension` replaced for the metadata file.
+    fn path(&self, url: &str, extension: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}", fnv1a(url)))
+            .with_extension(extension)
+    }
+
+    /// Reads the cached entry and body for `url`, if both are present, readable and belong
+    /// together.
+    fn load(&self, url: &str) -> Option<(CacheEntry, String)> {
+        let entry = std::fs::read_to_string(self.path(url, "json")).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&entry).ok()?;
+        let body = std::fs::read_to_string(self.path(url, "html")).ok()?;
+        (entry.url == url && entry.body_hash == fnv1a(&body)).then_some((entry, body))
+    }
+
+    /// Writes the entry and, if given, the body for `url`, printing a warning if that fails. A
+    /// cache which cannot be written, e.g. because the disk is full, does not fail the request.
+    fn store_or_warn(&self, entry: &CacheEntry, body: Option<&str>) {
+        if let Err(error) = self.store(entry, body) {
+            eprintln!(
+                "Warning: could not cache {} in {}: {}",
+                entry.url,
+                self.dir.display(),
+                error
+            );
+        }
+    }
+
+    /// Writes the entry and, if given, the body for `url`. The body is written first, so the
+    /// entry is only replaced once the body it describes is in place.
+    fn store(&self, entry: &CacheEntry, body: Option<&str>) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        if let Some(body) = body {
+            crate::write_atomic(&self.path(&entry.url, "html"), body)?;
+        }
+        let json = serde_json::to_string(entry)
+            .map_err(|error| Error::Serialization(error.to_string()))?;
+        crate::write_atomic(&self.path(&entry.url, "json"), &json)
+    }
+}
+
+impl Fetcher for CachingFetcher {
+    fn fetch(&self, url: &str) -> Result<String> {
+        let now = now();
+        let cached = match self.mode {
+            CacheMode::Normal => self.load(url),
+            CacheMode::Refresh => None,
+        };
+
+        if let Some((entry, body)) = &cached
+            && entry.is_fresh(now)
+        {
+            return Ok(body.clone());
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some((entry, _)) = &cached {
+            let validators = [
+                (IF_NONE_MATCH, &entry.etag),
+                (IF_MODIFIED_SINCE, &entry.last_modified),
+            ];
+            for (name, value) in validators {
+                if let Some(value) = value.as_deref().and_then(|value| value.parse().ok()) {
+                    headers.insert(name, value);
+                }
+            }
+        }
+
+        let response = self.http.send(url, headers)?;
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some((mut entry, body)) = cached
+        {
+            entry.update(response.headers(), now);
+            self.store_or_warn(&entry, None);
+            return Ok(body);
+        }
+
+        let response = response.error_for_status()?;
+        let headers = response.headers().clone();
+        let body = response.text()?;
+
+        if !has_directive(&headers, "no-store") {
+            let mut entry = CacheEntry {
+                url: url.to_string(),
+                etag: None,
+                last_modified: None,
+                fetched_at: now,
+                max_age: 0,
+                body_hash: fnv1a(&body),
+            };
+            entry.update(&headers, now);
+            self.store_or_warn(&entry, Some(&body));
+        }
+
+        Ok(body)
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Returns `Cache-Control: max-age` in seconds, or zero if absent or `no-cache` is set.
+fn max_age(headers: &HeaderMap) -> u64 {
+    if has_directive(headers, "no-cache") {
+        return 0;
+    }
+
+    cache_directives(headers)
+        .find_map(|directive| directive.strip_prefix("max-age=")?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Returns `true` if `Cache-Control` contains `name`.
+fn has_directive(headers: &HeaderMap, name: &str) -> bool {
+    cache_directives(headers).any(|directive| directive == name)
+}
+
+/// Lowercased `Cache-Control` directives.
+fn cache_directives(headers: &HeaderMap) -> impl Iterator<Item = String> + '_ {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().to_ascii_lowercase())
+}
+
+/// 64-bit FNV-1a hash, used for cache file names and body checks because it is stable across Rust
+/// releases.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn temp_cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "apple_releases-cache-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_cache_revalidates_with_etag() {
+    use crate::fetcher::{http_response, serve};
+
+    let dir = temp_cache_dir("etag");
+    let (url, server) = serve(vec![
+        http_response(
+            "200 OK",
+            &[
+                "ETag: \"v1\"",
+                "Last-Modified: Tue, 15 Nov 2022 08:00:00 GMT",
+            ],
+            "<html>v1</html>",
+        ),
+        http_response("304 Not Modified", &[], ""),
+    ]);
+
+    let fetcher = CachingFetcher::new(&dir, CacheMode::Normal).unwrap();
+    assert_eq!(fetcher.fetch(&url).unwrap(), "<html>v1</html>");
+    assert_eq!(fetcher.fetch(&url).unwrap(), "<html>v1</html>");
+
+    let requests = server.join().unwrap();
+    assert!(!requests[0].to_lowercase().contains("if-none-match"));
+    assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+    assert!(
+        requests[1]
+            .to_lowercase()
+            .contains("if-modified-since: tue, 15 nov 2022 08:00:00 gmt")
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cache_serves_fresh_entries() {
+    use crate::fetcher::{http_response, serve};
+
+    let dir = temp_cache_dir("fresh");
+    let (url, server) = serve(vec![
+        http_response("200 OK", &["Cache-Control: public, max-age=3600"], "v1"),
+        http_response("200 OK", &[], "v2"),
+    ]);
+
+    let fetcher = CachingFetcher::new(&dir, CacheMode::Normal).unwrap();
+    assert_eq!(fetcher.fetch(&url).unwrap(), "v1");
+    // Fresh, so no request is made
+    assert_eq!(fetcher.fetch(&url).unwrap(), "v1");
+
+    // Refresh ignores the fresh entry and updates the cache
+    let refreshing = CachingFetcher::new(&dir, CacheMode::Refresh).unwrap();
+    assert_eq!(refreshing.fetch(&url).unwrap(), "v2");
+    assert_eq!(server.join().unwrap().len(), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cache_skips_no_store() {
+    use crate::fetcher::{http_response, serve};
+
+    let dir = temp_cache_dir("no-store");
+    let (url, server) = serve(vec![http_response(
+        "200 OK",
+        &["Cache-Control: no-store"],
+        "v1",
+    )]);
+
+    let fetcher = CachingFetcher::new(&dir, CacheMode::Normal).unwrap();
+    assert_eq!(fetcher.fetch(&url).unwrap(), "v1");
+    assert!(fetcher.load(&url).is_none());
+    server.join().unwrap();
+}
+
+#[test]
+fn test_cache_write_failure() {
+    use crate::fetcher::{http_response, serve};
+
+    // A file where the cache directory should be, so nothing can be written
+    let dir = temp_cache_dir("unwritable");
+    std::fs::write(&dir, "").unwrap();
+    let (url, server) = serve(vec![http_response("200 OK", &[], "v1")]);
+
+    let fetcher = CachingFetcher::new(&dir, CacheMode::Normal).unwrap();
+    assert_eq!(fetcher.fetch(&url).unwrap(), "v1");
+    server.join().unwrap();
+    std::fs::remove_file(&dir).unwrap();
+}
+
+#[test]
+fn test_cache_rejects_mismatched_body() {
+    use crate::fetcher::{http_response, serve};
+
+    let dir = temp_cache_dir("mismatch");
+    let (url, server) = serve(vec![
+        http_response("200 OK", &["Cache-Control: max-age=3600"], "v1"),
+        http_response("200 OK", &[], "v2"),
+    ]);
+
+    let fetcher = CachingFetcher::new(&dir, CacheMode::Normal).unwrap();
+    assert_eq!(fetcher.fetch(&url).unwrap(), "v1");
+    assert!(fetcher.load(&url).is_some());
+
+    // A body replaced without its metadata, as by an interrupted write, is not served
+    std::fs::write(fetcher.path(&url, "html"), "partial").unwrap();
+    assert!(fetcher.load(&url).is_none());
+    assert_eq!(fetcher.fetch(&url).unwrap(), "v2");
+    assert_eq!(fetcher.load(&url).unwrap().1, "v2");
+
+    assert_eq!(server.join().unwrap().len(), 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_max_age() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(max_age(&headers), 0);
+
+    headers.insert(CACHE_CONTROL, "public, Max-Age=600".parse().unwrap());
+    assert_eq!(max_age(&headers), 600);
+
+    headers.insert(CACHE_CONTROL, "no-cache, max-age=600".parse().unwrap());
+    assert_eq!(max_age(&headers), 0);
+}
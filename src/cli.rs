@@ -2,28 +2,425 @@
 //! cli.rs
 //!
 
-use clap::{Arg, ArgAction, Command};
+use apple_releases::config::SelectorConfig;
+use apple_releases::date_spec::parse_date_spec;
+use apple_releases::rss::APPLE_DEV_RELEASES_RSS;
+use apple_releases::{APPLE_DEV_RELEASES, Error, OutputFormat, Product, Source};
+use chrono::{Local, NaiveDate};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::time::Duration;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Parses command line arguments.
 pub(crate) fn cli() -> Command {
-    Command::new("apple_releases")
+    let command = Command::new("apple_releases")
         .about("CLI for the Apple Developer News RSS feed")
         .version(VERSION)
         .author("Ben Chatelain")
+        .args(list_args())
         .arg(
-            // --all
-            Arg::new("all")
-                .long("all")
-                .short('a')
-                .help("Show all releases")
-                .action(ArgAction::SetTrue),
+            // --base-url <url>
+            Arg::new("base-url")
+                .long("base-url")
+                .help("URL of the releases page, e.g. a local stand-in server")
+                .value_name("URL")
+                .value_parser(parse_url)
+                .default_value(APPLE_DEV_RELEASES)
+                .global(true),
+        )
+        .arg(
+            // --source <source>
+            Arg::new("source")
+                .long("source")
+                .help("Read the HTML page, the RSS feed, or the page falling back to the feed")
+                .value_name("SOURCE")
+                .value_parser(Source::NAMES)
+                .ignore_case(true)
+                .default_value("auto")
+                .global(true),
+        )
+        .arg(
+            // --feed-url <url>
+            Arg::new("feed-url")
+                .long("feed-url")
+                .help("URL of the RSS feed")
+                .value_name("URL")
+                .value_parser(parse_url)
+                .default_value(APPLE_DEV_RELEASES_RSS)
+                .global(true),
+        )
+        .arg(
+            // --no-cache
+            Arg::new("no-cache")
+                .long("no-cache")
+                .help("Neither read nor write the HTTP cache")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("refresh")
+                .global(true),
+        )
+        .arg(
+            // --refresh
+            Arg::new("refresh")
+                .long("refresh")
+                .help("Download the page again even if the cached copy is fresh")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            // --config <path>
+            Arg::new("config")
+                .long("config")
+                .help("Configuration file [default: <config dir>/apple_releases/config.toml]")
+                .value_name("PATH")
+                .global(true),
+        )
+        .arg(
+            // --connect-timeout <seconds>
+            Arg::new("connect-timeout")
+                .long("connect-timeout")
+                .help("Seconds allowed to establish a connection")
+                .value_name("SECONDS")
+                .value_parser(parse_seconds)
+                .global(true),
+        )
+        .arg(
+            // --timeout <seconds>
+            Arg::new("timeout")
+                .long("timeout")
+                .help("Seconds allowed for each request, including reading the response")
+                .value_name("SECONDS")
+                .value_parser(parse_seconds)
+                .global(true),
+        )
+        .arg(
+            // --retries <count>
+            Arg::new("retries")
+                .long("retries")
+                .help("Times to retry after a connection error, timeout or 5xx response")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(u32))
+                .global(true),
+        )
+        .arg(
+            // --proxy <url>
+            Arg::new("proxy")
+                .long("proxy")
+                .help("Proxy for all requests, overriding HTTPS_PROXY and HTTP_PROXY")
+                .value_name("URL")
+                .value_parser(parse_url)
+                .global(true),
+        )
+        .arg(
+            // --ca-bundle <path>
+            Arg::new("ca-bundle")
+                .long("ca-bundle")
+                .help("PEM file of extra root certificates to trust")
+                .value_name("PATH")
+                .global(true),
+        )
+        .arg(
+            // --selector <field>=<css>
+            Arg::new("selector")
+                .long("selector")
+                .help("CSS selector to try first for part of the page, e.g. title=h2 (repeatable)")
+                .long_help(
+                    "CSS selector to try before the configured and built-in ones for part of the \
+                    page, given as FIELD=CSS, e.g. 'title=h2.release-title'. Fields are article, \
+                    title, date, body, links, permalink and next_page. Can be repeated.",
+                )
+                .value_name("FIELD=CSS")
+                .value_parser(parse_selector)
+                .action(ArgAction::Append)
+                .global(true),
+        )
+        .arg(
+            // --output <format>
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .help("Output format")
+                .value_name("FORMAT")
+                .value_parser(OutputFormat::NAMES)
+                .ignore_case(true)
+                .default_value("text")
+                .global(true),
+        )
+        .subcommand(
+            Command::new("fetch")
+                .about("Download the releases page without parsing it")
+                .arg(
+                    // --save <path>
+                    Arg::new("save")
+                        .long("save")
+                        .short('s')
+                        .help("File to save the page to, for later use with --input")
+                        .value_name("PATH")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("new")
+                .about("Print only articles not seen by a previous run, and remember them")
+                .long_about(
+                    "Print only articles not seen by a previous run, and remember them. Exits with \
+                    status 3 if there is nothing new.",
+                )
+                .args(list_args())
+                .arg(
+                    // --state <path>
+                    Arg::new("state")
+                        .long("state")
+                        .help(
+                            "File of articles already seen \
+                            [default: <data dir>/apple_releases/state.json]",
+                        )
+                        .value_name("PATH"),
+                ),
+        )
+        .subcommand(
+            Command::new("backfill")
+                .about("Follow links to older pages and print every article found")
+                .arg(
+                    // --delay <seconds>
+                    Arg::new("delay")
+                        .long("delay")
+                        .help("Seconds to wait between page requests")
+                        .value_name("SECONDS")
+                        .value_parser(parse_seconds)
+                        .default_value("1"),
+                )
+                .arg(
+                    // --max-pages <count>
+                    Arg::new("max-pages")
+                        .long("max-pages")
+                        .help("Stop after fetching this many pages")
+                        .value_name("COUNT")
+                        .value_parser(clap::value_parser!(usize)),
+                ),
+        )
+        .subcommand(
+            Command::new("watch")
+                .about("Keep polling for new articles, printing an event for each")
+                .long_about(
+                    "Keep polling for new articles, printing an event for each as a line of JSON \
+                    and optionally running a command. Without --state, articles present at the \
+                    first poll are not reported. Stops cleanly on SIGINT or SIGTERM.",
+                )
+                .args(list_args())
+                .arg(
+                    // --interval <duration>
+                    Arg::new("interval")
+                        .long("interval")
+                        .help("Time between polls, e.g. 90s, 15m or 1h, varied by up to a tenth")
+                        .value_name("DURATION")
+                        .value_parser(parse_interval)
+                        .default_value("15m"),
+                )
+                .arg(
+                    // --exec <command>
+                    Arg::new("exec")
+                        .long("exec")
+                        .help("Command to run for each new article, e.g. 'notify {product} {version}'")
+                        .long_help(
+                            "Command to run with sh for each new article. Placeholders named after \
+                            the CSV columns, such as {title}, {product}, {version}, {stage} and \
+                            {release_notes_url}, are replaced by quoted values.",
+                        )
+                        .value_name("COMMAND"),
+                )
+                .arg(
+                    // --state <path>
+                    Arg::new("state")
+                        .long("state")
+                        .help("File of articles already seen, shared with the new command")
+                        .value_name("PATH"),
+                ),
         )
         .after_help(
             "This tool parses the content of the Apple Developer Software Updates page: \
             https://developer.apple.com/news/releases/",
+        );
+
+    #[cfg(feature = "sqlite")]
+    let command = command
+        .arg(
+            // --history
+            Arg::new("history")
+                .long("history")
+                .help("Store fetched articles and list every article stored so far; new and watch only store them")
+                .action(ArgAction::SetTrue)
+                .global(true),
         )
+        .arg(
+            // --db <path>
+            Arg::new("db")
+                .long("db")
+                .help("Database for --history [default: <data dir>/apple_releases/releases.sqlite]")
+                .value_name("PATH")
+                .global(true),
+        );
+
+    command
+}
+
+/// Arguments selecting and filtering articles, shared by the default command, `new` and `watch`.
+fn list_args() -> Vec<Arg> {
+    vec![
+        // --all
+        Arg::new("all")
+            .long("all")
+            .short('a')
+            .help("Show all releases")
+            .action(ArgAction::SetTrue),
+        // --input <path>
+        Arg::new("input")
+            .long("input")
+            .short('i')
+            .help("Parse a saved releases page instead of fetching it ('-' reads stdin)")
+            .value_name("PATH"),
+        // --product <name>
+        Arg::new("product")
+            .long("product")
+            .short('p')
+            .help("Only show releases of this product (repeatable)")
+            .value_name("PRODUCT")
+            .value_parser(parse_product)
+            .action(ArgAction::Append),
+        // --exclude-product <name>
+        Arg::new("exclude-product")
+            .long("exclude-product")
+            .short('x')
+            .help("Hide releases of this product (repeatable)")
+            .value_name("PRODUCT")
+            .value_parser(parse_product)
+            .action(ArgAction::Append),
+        // --since <date>
+        Arg::new("since")
+            .long("since")
+            .help("Only show articles published on or after this date")
+            .long_help(
+                "Only show articles published on or after this date. Accepts YYYY-MM-DD, \
+                today, yesterday, a duration such as 30d, 2w, 6m or 1y, or last-wwdc.",
+            )
+            .value_name("DATE")
+            .value_parser(parse_date),
+        // --until <date>
+        Arg::new("until")
+            .long("until")
+            .help("Only show articles published on or before this date")
+            .long_help(
+                "Only show articles published on or before this date. Accepts the same forms \
+                as --since.",
+            )
+            .value_name("DATE")
+            .value_parser(parse_date),
+    ]
+}
+
+/// Parses a `--product` or `--exclude-product` value. Names which are not recognized select
+/// releases of `Product::Other` products with that name.
+fn parse_product(name: &str) -> Result<Product, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("product name must not be empty".to_string());
+    }
+    Ok(Product::from_name(name))
+}
+
+/// Rejects arguments which `backfill` would otherwise ignore. It crawls the HTML pages starting
+/// at `--base-url` over the network, so it cannot read a saved page or the RSS feed.
+///
+/// # Arguments
+///
+/// - `args` - The matches for the whole command line.
+/// - `backfill_args` - The matches for the `backfill` subcommand.
+pub(crate) fn check_backfill(
+    args: &ArgMatches,
+    backfill_args: &ArgMatches,
+) -> apple_releases::Result<()> {
+    // Global arguments are propagated to the subcommand, wherever they were given
+    let given =
+        |matches: &ArgMatches, name| matches.value_source(name) == Some(ValueSource::CommandLine);
+
+    let source = backfill_args.get_one::<String>("source").unwrap();
+    let unsupported = [
+        (given(args, "input"), "--input"),
+        (
+            given(backfill_args, "source") && !source.eq_ignore_ascii_case("html"),
+            "--source",
+        ),
+        (given(backfill_args, "feed-url"), "--feed-url"),
+    ];
+    match unsupported.iter().find(|(given, _)| *given) {
+        Some((_, flag)) => Err(Error::Argument(format!(
+            "{} cannot be used with backfill, which only crawls the HTML pages at --base-url",
+            flag
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Parses a `--base-url` value.
+fn parse_url(url: &str) -> Result<String, String> {
+    url::Url::parse(url)
+        .map(String::from)
+        .map_err(|error| error.to_string())
+}
+
+/// Parses a `--connect-timeout` or `--timeout` value in seconds.
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .map_err(|error| error.to_string())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).map_err(|error| error.to_string()))
+}
+
+/// Parses a `--interval` value: a number of seconds, or a number followed by `s`, `m`, `h` or `d`.
+fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let (number, unit) = match interval.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(index) => interval.split_at(index),
+        None => (interval, "s"),
+    };
+    let scale = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 60.0 * 60.0,
+        "d" => 24.0 * 60.0 * 60.0,
+        _ => return Err(format!("unknown unit '{}'; use s, m, h or d", unit)),
+    };
+
+    let seconds = parse_seconds(number)?;
+    if seconds.is_zero() {
+        return Err("interval must be greater than zero".to_string());
+    }
+    Duration::try_from_secs_f64(seconds.as_secs_f64() * scale)
+        .map_err(|_| format!("interval '{}' is too long", interval))
+}
+
+/// Parses a `--selector` value into its field and CSS selector.
+fn parse_selector(value: &str) -> Result<(String, String), String> {
+    let (field, css) = value
+        .split_once('=')
+        .ok_or_else(|| "expected FIELD=CSS".to_string())?;
+    let field = field.trim();
+    if !SelectorConfig::FIELDS.contains(&field) {
+        return Err(format!(
+            "unknown field '{}'; expected one of {}",
+            field,
+            SelectorConfig::FIELDS.join(", ")
+        ));
+    }
+    if css.trim().is_empty() {
+        return Err("empty selector".to_string());
+    }
+    Ok((field.to_string(), css.trim().to_string()))
+}
+
+/// Parses a `--since` or `--until` value relative to the local date.
+fn parse_date(spec: &str) -> Result<NaiveDate, String> {
+    parse_date_spec(spec, Local::now().date_naive()).map_err(|error| error.to_string())
 }
 
 /* ---------------------------------------------------------------------------------------------- */
@@ -32,3 +429,368 @@ pub(crate) fn cli() -> Command {
 fn verify_cli() {
     cli().debug_assert();
 }
+
+#[test]
+fn parse_output_format() {
+    let args = cli().get_matches_from(["apple_releases", "--output", "json"]);
+    let format: OutputFormat = args.get_one::<String>("output").unwrap().parse().unwrap();
+    assert_eq!(format, OutputFormat::Json);
+
+    let args = cli().get_matches_from(["apple_releases"]);
+    let format: OutputFormat = args.get_one::<String>("output").unwrap().parse().unwrap();
+    assert_eq!(format, OutputFormat::Text);
+}
+
+#[test]
+fn parse_products() {
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "--product",
+        "xcode",
+        "-p",
+        "ipados",
+        "--exclude-product",
+        "mac",
+    ]);
+    let products: Vec<&Product> = args.get_many::<Product>("product").unwrap().collect();
+    assert_eq!(products, vec![&Product::Xcode, &Product::iPadOS]);
+    let excluded: Vec<&Product> = args
+        .get_many::<Product>("exclude-product")
+        .unwrap()
+        .collect();
+    assert_eq!(excluded, vec![&Product::macOS]);
+
+    let args = cli().get_matches_from(["apple_releases", "--product", "Final Cut Pro"]);
+    assert_eq!(
+        args.get_one::<Product>("product"),
+        Some(&Product::Other("Final Cut Pro".to_string()))
+    );
+
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "--product", " "])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_date_range() {
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "--since",
+        "2024-06-01",
+        "--until",
+        "today",
+    ]);
+    assert_eq!(
+        args.get_one::<NaiveDate>("since"),
+        NaiveDate::from_ymd_opt(2024, 6, 1).as_ref()
+    );
+    assert_eq!(
+        args.get_one::<NaiveDate>("until"),
+        Some(&Local::now().date_naive())
+    );
+
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "--since", "soon"])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_input_and_fetch() {
+    let args = cli().get_matches_from(["apple_releases", "--input", "-"]);
+    assert_eq!(args.get_one::<String>("input").unwrap(), "-");
+    assert!(args.subcommand().is_none());
+
+    let args = cli().get_matches_from(["apple_releases", "fetch", "--save", "page.html"]);
+    let (name, fetch) = args.subcommand().unwrap();
+    assert_eq!(name, "fetch");
+    assert_eq!(fetch.get_one::<String>("save").unwrap(), "page.html");
+
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "fetch"])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_base_url() {
+    let args = cli().get_matches_from(["apple_releases"]);
+    assert_eq!(
+        args.get_one::<String>("base-url").unwrap(),
+        APPLE_DEV_RELEASES
+    );
+
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "fetch",
+        "--save",
+        "page.html",
+        "--base-url",
+        "http://localhost:8080/releases/",
+    ]);
+    let (_, fetch) = args.subcommand().unwrap();
+    assert_eq!(
+        fetch.get_one::<String>("base-url").unwrap(),
+        "http://localhost:8080/releases/"
+    );
+
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "--base-url", "not a url"])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_cache_flags() {
+    let args = cli().get_matches_from(["apple_releases", "--refresh"]);
+    assert!(args.get_flag("refresh"));
+    assert!(!args.get_flag("no-cache"));
+
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "--refresh", "--no-cache"])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_http_settings() {
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "--timeout",
+        "2.5",
+        "--retries",
+        "0",
+        "fetch",
+        "--save",
+        "page.html",
+        "--proxy",
+        "http://proxy.example.com:8080",
+    ]);
+    assert_eq!(
+        args.get_one::<Duration>("timeout"),
+        Some(&Duration::from_millis(2500))
+    );
+    assert_eq!(args.get_one::<u32>("retries"), Some(&0));
+    let (_, fetch) = args.subcommand().unwrap();
+    assert_eq!(
+        fetch.get_one::<String>("proxy").unwrap(),
+        "http://proxy.example.com:8080/"
+    );
+
+    for invalid in [
+        ["--timeout", "-1"],
+        ["--connect-timeout", "soon"],
+        ["--retries", "-1"],
+    ] {
+        assert!(
+            cli()
+                .try_get_matches_from(["apple_releases", invalid[0], invalid[1]])
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn parse_selectors() {
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "--selector",
+        "title=h2.release-title",
+        "--selector",
+        "next_page = a.older",
+        "new",
+    ]);
+    let (_, new) = args.subcommand().unwrap();
+    let selectors: Vec<&(String, String)> = new
+        .get_many::<(String, String)>("selector")
+        .unwrap()
+        .collect();
+    assert_eq!(
+        selectors,
+        vec![
+            &("title".to_string(), "h2.release-title".to_string()),
+            &("next_page".to_string(), "a.older".to_string()),
+        ]
+    );
+
+    for invalid in ["h2", "headline=h2", "title="] {
+        assert!(
+            cli()
+                .try_get_matches_from(["apple_releases", "--selector", invalid])
+                .is_err(),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn parse_source() {
+    let args = cli().get_matches_from(["apple_releases"]);
+    let source: Source = args.get_one::<String>("source").unwrap().parse().unwrap();
+    assert_eq!(source, Source::Auto);
+    assert_eq!(
+        args.get_one::<String>("feed-url").unwrap(),
+        APPLE_DEV_RELEASES_RSS
+    );
+
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "fetch",
+        "-s",
+        "feed.xml",
+        "--source",
+        "RSS",
+    ]);
+    let (_, fetch) = args.subcommand().unwrap();
+    let source: Source = fetch.get_one::<String>("source").unwrap().parse().unwrap();
+    assert_eq!(source, Source::Rss);
+
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "--source", "atom"])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_backfill() {
+    let args = cli().get_matches_from(["apple_releases", "backfill", "-o", "ndjson"]);
+    let (name, backfill) = args.subcommand().unwrap();
+    assert_eq!(name, "backfill");
+    assert_eq!(
+        backfill.get_one::<Duration>("delay"),
+        Some(&Duration::from_secs(1))
+    );
+    assert_eq!(backfill.get_one::<usize>("max-pages"), None);
+    assert_eq!(backfill.get_one::<String>("output").unwrap(), "ndjson");
+
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "backfill",
+        "--delay",
+        "0.25",
+        "--max-pages",
+        "10",
+    ]);
+    let (_, backfill) = args.subcommand().unwrap();
+    assert_eq!(
+        backfill.get_one::<Duration>("delay"),
+        Some(&Duration::from_millis(250))
+    );
+    assert_eq!(backfill.get_one::<usize>("max-pages"), Some(&10));
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn parse_history() {
+    let args = cli().get_matches_from(["apple_releases", "--history", "--db", "releases.sqlite"]);
+    assert!(args.get_flag("history"));
+    assert_eq!(args.get_one::<String>("db").unwrap(), "releases.sqlite");
+
+    let args = cli().get_matches_from(["apple_releases", "backfill", "--history"]);
+    let (_, backfill) = args.subcommand().unwrap();
+    assert!(backfill.get_flag("history"));
+}
+
+#[test]
+fn parse_new() {
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "new",
+        "--product",
+        "xcode",
+        "--state",
+        "state.json",
+        "-o",
+        "json",
+    ]);
+    let (name, new) = args.subcommand().unwrap();
+    assert_eq!(name, "new");
+    let products: Vec<&Product> = new.get_many::<Product>("product").unwrap().collect();
+    assert_eq!(products, vec![&Product::Xcode]);
+    assert_eq!(new.get_one::<String>("state").unwrap(), "state.json");
+    assert_eq!(new.get_one::<String>("output").unwrap(), "json");
+
+    // Filters belong to listing commands only
+    assert!(
+        cli()
+            .try_get_matches_from(["apple_releases", "backfill", "--product", "xcode"])
+            .is_err()
+    );
+}
+
+#[test]
+fn parse_watch() {
+    let args = cli().get_matches_from([
+        "apple_releases",
+        "watch",
+        "--product",
+        "ios",
+        "--exec",
+        "notify {product} {version}",
+    ]);
+    let (name, watch) = args.subcommand().unwrap();
+    assert_eq!(name, "watch");
+    assert_eq!(
+        watch.get_one::<Duration>("interval"),
+        Some(&Duration::from_secs(15 * 60))
+    );
+    assert_eq!(
+        watch.get_one::<String>("exec").unwrap(),
+        "notify {product} {version}"
+    );
+
+    for (interval, expected) in [
+        ("90", Duration::from_secs(90)),
+        ("30s", Duration::from_secs(30)),
+        ("1.5h", Duration::from_secs(90 * 60)),
+        ("1d", Duration::from_secs(24 * 60 * 60)),
+    ] {
+        assert_eq!(parse_interval(interval), Ok(expected), "{}", interval);
+    }
+    for interval in ["", "0m", "15x", "m", "-1h", "1000000000000000d"] {
+        assert!(parse_interval(interval).is_err(), "{}", interval);
+    }
+}
+
+#[test]
+fn backfill_rejects_other_sources() {
+    let check = |argv: &[&str]| {
+        let args = cli().get_matches_from(argv);
+        let (_, backfill_args) = args.subcommand().unwrap();
+        check_backfill(&args, backfill_args)
+    };
+
+    assert!(check(&["apple_releases", "backfill"]).is_ok());
+    assert!(check(&["apple_releases", "backfill", "--source", "html"]).is_ok());
+    assert!(
+        check(&[
+            "apple_releases",
+            "--base-url",
+            "http://localhost:8080/",
+            "backfill"
+        ])
+        .is_ok()
+    );
+
+    for argv in [
+        &["apple_releases", "--input", "releases.html", "backfill"][..],
+        &["apple_releases", "--source", "rss", "backfill"],
+        &["apple_releases", "backfill", "--source", "auto"],
+        &[
+            "apple_releases",
+            "backfill",
+            "--feed-url",
+            "http://localhost:8080/rss",
+        ],
+    ] {
+        assert!(matches!(check(argv), Err(Error::Argument(_))), "{:?}", argv);
+    }
+}
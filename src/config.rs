@@ -0,0 +1,314 @@
+//!
+//! config.rs
+//!
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Deserializer};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Settings read from the configuration file.
+///
+/// The file is TOML, by default `config.toml` in the platform configuration directory
+/// (`~/.config/apple_releases/config.toml` on Linux). Every key is optional.
+///
+/// ```toml
+/// [http]
+/// connect_timeout = 10    # seconds
+/// timeout = 30            # seconds, for the whole request
+/// retries = 3
+/// backoff = 0.5           # seconds before the first retry, doubling after each
+/// proxy = "http://proxy.example.com:8080"
+/// ca_bundle = "/etc/ssl/certs/corporate.pem"
+///
+/// [selectors]
+/// title = ["h2.release-title", "h2"]
+/// date = "time.article-date"
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::config::Config;
+/// use std::time::Duration;
+///
+/// let config: Config = "[http]\nretries = 5".parse().unwrap();
+/// assert_eq!(config.http.retries, 5);
+/// assert_eq!(config.http.timeout, Duration::from_secs(30));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Network settings.
+    pub http: HttpConfig,
+
+    /// Extra CSS selectors for parsing the releases page.
+    pub selectors: SelectorConfig,
+}
+
+impl Config {
+    /// Returns the default configuration file path.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("config.toml"))
+    }
+
+    /// Reads the configuration file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The file to read. It must exist.
+    pub fn load(path: &Path) -> Result<Config> {
+        let text = std::fs::read_to_string(path)?;
+        text.parse().map_err(|error| match error {
+            Error::Config(message) => Error::Config(format!("{}: {}", path.display(), message)),
+            error => error,
+        })
+    }
+
+    /// Reads the configuration file at the default path, or returns the defaults if there is none.
+    pub fn load_default() -> Result<Config> {
+        match Config::default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|error| Error::Config(error.message().to_string()))
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Network settings for an [`HttpFetcher`](crate::fetcher::HttpFetcher).
+///
+/// Proxies named by the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables are used
+/// unless `proxy` is set.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Time allowed to establish a connection.
+    #[serde(deserialize_with = "seconds")]
+    pub connect_timeout: Duration,
+
+    /// Time allowed for a whole request, from connecting to reading the body.
+    #[serde(deserialize_with = "seconds")]
+    pub timeout: Duration,
+
+    /// Number of times a request is retried after a connection error, timeout or 5xx status.
+    pub retries: u32,
+
+    /// Delay before the first retry. Each retry doubles it, with random jitter.
+    #[serde(deserialize_with = "seconds")]
+    pub backoff: Duration,
+
+    /// Proxy for all requests, e.g. `http://proxy.example.com:8080`.
+    pub proxy: Option<String>,
+
+    /// PEM file of extra root certificates, e.g. for a TLS-intercepting proxy.
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            proxy: None,
+            ca_bundle: None,
+        }
+    }
+}
+
+/// CSS selectors for the parts of the releases page, for when Apple changes its markup.
+///
+/// Each key takes one selector or a list of them. They are tried in order, before the built-in
+/// selectors, and the first which matches anything is used. See
+/// [`Selectors`](crate::selectors::Selectors).
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SelectorConfig {
+    /// Each article's container, searched within the page.
+    #[serde(deserialize_with = "one_or_many")]
+    pub article: Vec<String>,
+
+    /// The title, searched within an article.
+    #[serde(deserialize_with = "one_or_many")]
+    pub title: Vec<String>,
+
+    /// The date, e.g. `November 15, 2022`, searched within an article.
+    #[serde(deserialize_with = "one_or_many")]
+    pub date: Vec<String>,
+
+    /// The descriptive text, searched within an article.
+    #[serde(deserialize_with = "one_or_many")]
+    pub body: Vec<String>,
+
+    /// Links such as release notes and downloads, searched within an article.
+    #[serde(deserialize_with = "one_or_many")]
+    pub links: Vec<String>,
+
+    /// Elements whose `data-href` is the article's permalink, searched within an article.
+    #[serde(deserialize_with = "one_or_many")]
+    pub permalink: Vec<String>,
+
+    /// The link to the next, older page, searched within the page.
+    #[serde(deserialize_with = "one_or_many")]
+    pub next_page: Vec<String>,
+}
+
+impl SelectorConfig {
+    /// Names of the parts of the page which take selectors.
+    pub const FIELDS: [&'static str; 7] = [
+        "article",
+        "title",
+        "date",
+        "body",
+        "links",
+        "permalink",
+        "next_page",
+    ];
+
+    /// Adds a selector to be tried before the others for a part of the page.
+    ///
+    /// # Arguments
+    ///
+    /// - `field` - One of [`SelectorConfig::FIELDS`].
+    /// - `selector` - The CSS selector. It is validated by
+    ///   [`Selectors::with_config`](crate::selectors::Selectors::with_config).
+    pub fn prepend(&mut self, field: &str, selector: String) -> Result<()> {
+        let selectors = match field {
+            "article" => &mut self.article,
+            "title" => &mut self.title,
+            "date" => &mut self.date,
+            "body" => &mut self.body,
+            "links" => &mut self.links,
+            "permalink" => &mut self.permalink,
+            "next_page" => &mut self.next_page,
+            _ => {
+                return Err(Error::Config(format!(
+                    "unknown selector '{}'; expected one of {}",
+                    field,
+                    Self::FIELDS.join(", ")
+                )));
+            }
+        };
+        selectors.insert(0, selector);
+        Ok(())
+    }
+}
+
+/// Deserializes a list of strings from either a string or an array of strings.
+fn one_or_many<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(selector) => vec![selector],
+        OneOrMany::Many(selectors) => selectors,
+    })
+}
+
+/// Deserializes a duration from a number of seconds, e.g. `30` or `0.5`.
+fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Duration, D::Error> {
+    let seconds = f64::deserialize(deserializer)?;
+    Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom)
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_parse_config() {
+    let config: Config = r#"
+        [http]
+        connect_timeout = 5
+        timeout = 12.5
+        retries = 0
+        proxy = "http://proxy.example.com:8080"
+        ca_bundle = "/etc/ssl/certs/corporate.pem"
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        config.http,
+        HttpConfig {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_millis(12500),
+            retries: 0,
+            backoff: Duration::from_millis(500),
+            proxy: Some("http://proxy.example.com:8080".to_string()),
+            ca_bundle: Some(PathBuf::from("/etc/ssl/certs/corporate.pem")),
+        }
+    );
+
+    assert_eq!("".parse::<Config>().unwrap(), Config::default());
+}
+
+#[test]
+fn test_parse_selector_config() {
+    let config: Config = r#"
+        [selectors]
+        title = ["h2.release-title", "h2"]
+        date = "time"
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(config.selectors.title, vec!["h2.release-title", "h2"]);
+    assert_eq!(config.selectors.date, vec!["time"]);
+    assert!(config.selectors.article.is_empty());
+
+    let mut selectors = config.selectors;
+    selectors.prepend("date", "p.date".to_string()).unwrap();
+    assert_eq!(selectors.date, vec!["p.date", "time"]);
+    assert!(matches!(
+        selectors.prepend("headline", "h1".to_string()),
+        Err(Error::Config(_))
+    ));
+}
+
+#[test]
+fn test_parse_invalid_config() {
+    for text in [
+        "[http]\nretries = \"many\"",
+        "[http]\ntimeout = -1",
+        "[http]\ntimeot = 5",
+        "[htp]",
+        "[selectors]\ntitel = \"h2\"",
+        "[selectors]\ntitle = 2",
+    ] {
+        assert!(
+            matches!(text.parse::<Config>(), Err(Error::Config(_))),
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_load_config() {
+    let path =
+        std::env::temp_dir().join(format!("apple_releases-config-{}.toml", std::process::id()));
+    std::fs::write(&path, "[http]\nretries = nope").unwrap();
+    let result = Config::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains(&path.display().to_string()), "{}", message);
+
+    assert!(matches!(Config::load(&path), Err(Error::Io(_))));
+}
@@ -0,0 +1,308 @@
+//!
+//! crawl.rs
+//!
+
+use crate::error::{Error, Result};
+use crate::fetcher::Fetcher;
+use crate::parse::{self, ParseResult, ParseWarning};
+use crate::selectors::Selectors;
+use scraper::Html;
+use std::collections::HashSet;
+use std::time::Duration;
+use url::Url;
+
+/// Collects the full history of articles by following the releases page's links to older pages.
+///
+/// Crawling stops at the last page, at `max_pages`, when a page links back to one already visited,
+/// or when a page has no articles which have not been seen already. Articles appearing on more than
+/// one page are kept once, in the position they were first seen.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::crawl::Crawler;
+/// use apple_releases::fetcher::MemoryFetcher;
+/// use std::time::Duration;
+///
+/// let fetcher = MemoryFetcher::new()
+///     .page("https://example.com/releases/", r#"
+///         <section class="article-content-container">
+///             <a class="article-title"><h2>iOS 16.2 (20C65)</h2></a>
+///             <p class="article-date">December 13, 2022</p>
+///         </section>
+///         <a rel="next" href="?page=2">Older</a>
+///     "#)
+///     .page("https://example.com/releases/?page=2", r#"
+///         <section class="article-content-container">
+///             <a class="article-title"><h2>iOS 16.1.2 (20B110)</h2></a>
+///             <p class="article-date">November 30, 2022</p>
+///         </section>
+///     "#);
+///
+/// let result = Crawler::new()
+///     .delay(Duration::ZERO)
+///     .crawl(&fetcher, "https://example.com/releases/")
+///     .unwrap();
+/// assert_eq!(result.articles.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Crawler {
+    /// Pause between page requests.
+    delay: Duration,
+
+    /// Maximum number of pages to fetch, or `None` for no limit.
+    max_pages: Option<usize>,
+
+    /// Where to find articles and the next page link.
+    selectors: Selectors,
+}
+
+impl Default for Crawler {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_secs(1),
+            max_pages: None,
+            selectors: Selectors::default(),
+        }
+    }
+}
+
+impl Crawler {
+    /// Creates a crawler with a one second delay between pages and no page limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the pause between page requests.
+    ///
+    /// # Arguments
+    ///
+    /// - `delay` - Time to wait before fetching each page after the first.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Limits the number of pages fetched.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_pages` - The most pages to fetch, including the first.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Sets the selectors used to parse each page.
+    ///
+    /// # Arguments
+    ///
+    /// - `selectors` - Where to find articles and the next page link.
+    pub fn selectors(mut self, selectors: Selectors) -> Self {
+        self.selectors = selectors;
+        self
+    }
+
+    /// Crawls from `url` through the pages it links to as older.
+    ///
+    /// # Arguments
+    ///
+    /// - `fetcher` - Retrieves each page.
+    /// - `url` - The first page, usually [`APPLE_DEV_RELEASES`](crate::APPLE_DEV_RELEASES).
+    ///
+    /// # Returns
+    ///
+    /// The unique articles, newest page first. An error fetching the first page is returned as an
+    /// error; an error on a later page ends the crawl and is reported as a warning.
+    pub fn crawl(&self, fetcher: &dyn Fetcher, url: &str) -> Result<ParseResult> {
+        let mut result = ParseResult::default();
+        let mut seen = HashSet::new();
+        let mut visited = HashSet::new();
+
+        let mut next = Some(Url::parse(url).map_err(|source| Error::Url {
+            value: url.to_string(),
+            source,
+        })?);
+        while let Some(page_url) = next.take() {
+            if self.max_pages.is_some_and(|max| visited.len() >= max)
+                || !visited.insert(page_url.clone())
+            {
+                break;
+            }
+            if visited.len() > 1 {
+                std::thread::sleep(self.delay);
+            }
+
+            let content = match fetcher.fetch(page_url.as_str()) {
+                Ok(content) => content,
+                Err(error) if visited.len() == 1 => return Err(error),
+                Err(error) => {
+                    result.warnings.push(ParseWarning {
+                        title: None,
+                        html: String::new(),
+                        error,
+                    });
+                    break;
+                }
+            };
+
+            next = match next_page(&content, &page_url, &self.selectors) {
+                Ok(next) => next,
+                Err(error) => {
+                    result.warnings.push(ParseWarning {
+                        title: None,
+                        html: String::new(),
+                        error,
+                    });
+                    None
+                }
+            };
+
+            let page = parse::parse_articles_with(content, &self.selectors, &page_url);
+            result.warnings.extend(page.warnings);
+
+            let count = result.articles.len();
+            for article in page.articles {
+                if seen.insert(article.key()) {
+                    result.articles.push(article);
+                }
+            }
+            if result.articles.len() == count {
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Finds the next page link in `content`, resolved against the page's URL.
+fn next_page(content: &str, page_url: &Url, selectors: &Selectors) -> Result<Option<Url>> {
+    let document = Html::parse_document(content);
+    let Some(link) = parse::parse_next_page_link(&document.root_element(), &selectors.next_page)
+    else {
+        return Ok(None);
+    };
+
+    page_url.join(&link).map(Some).map_err(|source| Error::Url {
+        value: link,
+        source,
+    })
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn test_page(titles: &[&str], next: Option<&str>) -> String {
+    let mut html = String::new();
+    for title in titles {
+        html.push_str(&format!(
+            r#"<section class="article-content-container">
+                <a class="article-title"><h2>{}</h2></a>
+                <p class="article-date">November 30, 2022</p>
+            </section>"#,
+            title
+        ));
+    }
+    if let Some(next) = next {
+        html.push_str(&format!(
+            r#"<nav class="pagination"><a class="next" href="{}">Older</a></nav>"#,
+            next
+        ));
+    }
+    html
+}
+
+#[cfg(test)]
+fn titles(result: &ParseResult) -> Vec<&str> {
+    result
+        .articles
+        .iter()
+        .map(|article| article.title.as_str())
+        .collect()
+}
+
+#[test]
+fn test_crawl_deduplicates() {
+    use crate::fetcher::MemoryFetcher;
+
+    let fetcher = MemoryFetcher::new()
+        .page(
+            "https://example.com/releases/",
+            test_page(&["iOS 16.2", "iOS 16.1.2"], Some("/releases/?page=2")),
+        )
+        .page(
+            "https://example.com/releases/?page=2",
+            test_page(&["iOS 16.1.2", "iOS 16.1.1"], Some("?page=3")),
+        )
+        .page(
+            "https://example.com/releases/?page=3",
+            test_page(&["iOS 16.1"], None),
+        );
+
+    let crawler = Crawler::new().delay(Duration::ZERO);
+    let result = crawler
+        .crawl(&fetcher, "https://example.com/releases/")
+        .unwrap();
+    assert_eq!(
+        titles(&result),
+        vec!["iOS 16.2", "iOS 16.1.2", "iOS 16.1.1", "iOS 16.1"]
+    );
+    assert!(result.warnings.is_empty());
+
+    let result = crawler
+        .max_pages(2)
+        .crawl(&fetcher, "https://example.com/releases/")
+        .unwrap();
+    assert_eq!(titles(&result).len(), 3);
+}
+
+#[test]
+fn test_crawl_stops() {
+    use crate::fetcher::MemoryFetcher;
+
+    // A page linking to itself
+    let fetcher = MemoryFetcher::new().page(
+        "https://example.com/releases/",
+        test_page(&["iOS 16.2"], Some("/releases/")),
+    );
+    let crawler = Crawler::new().delay(Duration::ZERO);
+    let result = crawler
+        .crawl(&fetcher, "https://example.com/releases/")
+        .unwrap();
+    assert_eq!(titles(&result), vec!["iOS 16.2"]);
+
+    // A page with nothing new
+    let fetcher = MemoryFetcher::new()
+        .page(
+            "https://example.com/releases/",
+            test_page(&["iOS 16.2"], Some("?page=2")),
+        )
+        .page(
+            "https://example.com/releases/?page=2",
+            test_page(&["iOS 16.2"], Some("?page=3")),
+        );
+    let result = crawler
+        .crawl(&fetcher, "https://example.com/releases/")
+        .unwrap();
+    assert_eq!(titles(&result), vec!["iOS 16.2"]);
+    assert!(result.warnings.is_empty());
+
+    // A missing later page
+    let fetcher = MemoryFetcher::new().page(
+        "https://example.com/releases/",
+        test_page(&["iOS 16.2"], Some("?page=2")),
+    );
+    let result = crawler
+        .crawl(&fetcher, "https://example.com/releases/")
+        .unwrap();
+    assert_eq!(titles(&result), vec!["iOS 16.2"]);
+    assert!(matches!(result.warnings[0].error, Error::Io(_)));
+
+    // A missing first page
+    assert!(
+        crawler
+            .crawl(&MemoryFetcher::new(), "https://example.com/releases/")
+            .is_err()
+    );
+}
@@ -0,0 +1,182 @@
+//!
+//! date_spec.rs
+//!
+
+use crate::error::{Error, Result};
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+
+/// Known WWDC keynote dates. Years not listed fall back to [`estimated_wwdc`].
+const WWDC_KEYNOTES: [(i32, u32, u32); 7] = [
+    (2019, 6, 3),
+    (2020, 6, 22),
+    (2021, 6, 7),
+    (2022, 6, 6),
+    (2023, 6, 5),
+    (2024, 6, 10),
+    (2025, 6, 9),
+];
+
+/// Parses a date given on the command line.
+///
+/// Accepted forms:
+///
+/// - An ISO date: `2024-06-01`
+/// - `today` or `yesterday`
+/// - A duration before `today`: `30d`, `2w`, `6m` or `1y`
+/// - `last-wwdc`: the most recent WWDC keynote on or before `today`
+///
+/// # Arguments
+///
+/// - `spec` - The date to parse.
+/// - `today` - The date relative forms are measured from.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::date_spec::parse_date_spec;
+/// use chrono::NaiveDate;
+///
+/// let today = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+/// assert_eq!(parse_date_spec("30d", today).unwrap(), NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+/// assert_eq!(parse_date_spec("last-wwdc", today).unwrap(), NaiveDate::from_ymd_opt(2024, 6, 10).unwrap());
+/// ```
+pub fn parse_date_spec(spec: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let spec = spec.trim().to_ascii_lowercase();
+
+    match spec.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return days_before(today, 1, &spec),
+        "last-wwdc" | "wwdc" => return Ok(last_wwdc(today)),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&spec, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    let invalid = || Error::Argument(format!("invalid date '{}'", spec));
+
+    let (unit_index, _) = spec.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = spec.split_at(unit_index);
+    let amount: u32 = amount.parse().map_err(|_| invalid())?;
+
+    match unit {
+        "d" => days_before(today, amount.into(), &spec),
+        "w" => days_before(today, u64::from(amount) * 7, &spec),
+        "m" => today
+            .checked_sub_months(Months::new(amount))
+            .ok_or_else(invalid),
+        "y" => amount
+            .checked_mul(12)
+            .and_then(|months| today.checked_sub_months(Months::new(months)))
+            .ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+/// Subtracts `days` from `today`.
+fn days_before(today: NaiveDate, days: u64, spec: &str) -> Result<NaiveDate> {
+    today
+        .checked_sub_days(Days::new(days))
+        .ok_or_else(|| Error::Argument(format!("invalid date '{}'", spec)))
+}
+
+/// Returns the most recent WWDC keynote on or before `today`.
+fn last_wwdc(today: NaiveDate) -> NaiveDate {
+    let this_year = wwdc_keynote(today.year());
+    if this_year <= today {
+        this_year
+    } else {
+        wwdc_keynote(today.year() - 1)
+    }
+}
+
+/// Returns the WWDC keynote date for `year`.
+fn wwdc_keynote(year: i32) -> NaiveDate {
+    WWDC_KEYNOTES
+        .iter()
+        .find(|(known_year, _, _)| *known_year == year)
+        .and_then(|(year, month, day)| NaiveDate::from_ymd_opt(*year, *month, *day))
+        .unwrap_or_else(|| estimated_wwdc(year))
+}
+
+/// Estimates the WWDC keynote as the first Monday on or after June 5.
+fn estimated_wwdc(year: i32) -> NaiveDate {
+    let mut date = NaiveDate::from_ymd_opt(year, 6, 5).unwrap();
+    while date.weekday() != Weekday::Mon {
+        date = date.succ_opt().unwrap();
+    }
+    date
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn test_parse_absolute_date() {
+    let today = ymd(2024, 7, 1);
+    assert_eq!(
+        parse_date_spec("2024-06-01", today).unwrap(),
+        ymd(2024, 6, 1)
+    );
+    assert_eq!(parse_date_spec("today", today).unwrap(), today);
+    assert_eq!(
+        parse_date_spec("Yesterday", today).unwrap(),
+        ymd(2024, 6, 30)
+    );
+}
+
+#[test]
+fn test_parse_relative_date() {
+    let today = ymd(2024, 7, 1);
+    assert_eq!(parse_date_spec("30d", today).unwrap(), ymd(2024, 6, 1));
+    assert_eq!(parse_date_spec("2w", today).unwrap(), ymd(2024, 6, 17));
+    assert_eq!(parse_date_spec("6m", today).unwrap(), ymd(2024, 1, 1));
+    assert_eq!(parse_date_spec("1y", today).unwrap(), ymd(2023, 7, 1));
+}
+
+#[test]
+fn test_parse_last_wwdc() {
+    assert_eq!(
+        parse_date_spec("last-wwdc", ymd(2024, 7, 1)).unwrap(),
+        ymd(2024, 6, 10)
+    );
+    assert_eq!(
+        parse_date_spec("last-wwdc", ymd(2024, 6, 10)).unwrap(),
+        ymd(2024, 6, 10)
+    );
+    assert_eq!(
+        parse_date_spec("last-wwdc", ymd(2024, 6, 9)).unwrap(),
+        ymd(2023, 6, 5)
+    );
+    assert_eq!(
+        parse_date_spec("last-wwdc", ymd(2027, 1, 1)).unwrap(),
+        ymd(2026, 6, 8)
+    );
+}
+
+#[test]
+fn test_parse_invalid_date() {
+    let today = ymd(2024, 7, 1);
+    for spec in [
+        "",
+        "d",
+        "30",
+        "thirty-days",
+        "2024-13-01",
+        "-5d",
+        "5q",
+        "30é",
+        "é",
+    ] {
+        assert!(
+            matches!(parse_date_spec(spec, today), Err(Error::Argument(_))),
+            "{}",
+            spec
+        );
+    }
+}
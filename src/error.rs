@@ -0,0 +1,158 @@
+//!
+//! error.rs
+//!
+
+use std::fmt::{Display, Formatter};
+
+/// Result type used throughout the crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors which can occur while fetching or parsing releases.
+///
+/// Marked `#[non_exhaustive]` because variants depend on the enabled features, e.g. `Database`
+/// with `sqlite`, so matches outside this crate need a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A network request failed.
+    Network(reqwest::Error),
+
+    /// An expected element was missing from the HTML.
+    Html(String),
+
+    /// The RSS feed was malformed or an item was missing an expected element.
+    Feed(String),
+
+    /// A date string could not be parsed.
+    Date {
+        /// The text which failed to parse.
+        value: String,
+        /// The underlying parse error.
+        source: chrono::ParseError,
+    },
+
+    /// A version string could not be parsed.
+    Version {
+        /// The text which failed to parse.
+        value: String,
+        /// Description of the failure.
+        message: String,
+    },
+
+    /// A URL could not be parsed or joined.
+    Url {
+        /// The text which failed to parse.
+        value: String,
+        /// The underlying parse error.
+        source: url::ParseError,
+    },
+
+    /// Reading or writing failed.
+    Io(std::io::Error),
+
+    /// Articles could not be serialized to the requested format.
+    Serialization(String),
+
+    /// A user-supplied argument, such as a date or format name, was invalid.
+    Argument(String),
+
+    /// The configuration file could not be parsed.
+    Config(String),
+
+    /// A query against the article store failed.
+    #[cfg(feature = "sqlite")]
+    Database(rusqlite::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Network(error) => write!(formatter, "network error: {}", error),
+            Error::Html(message) => write!(formatter, "HTML structure error: {}", message),
+            Error::Feed(message) => write!(formatter, "RSS feed error: {}", message),
+            Error::Date { value, source } => {
+                write!(formatter, "invalid date '{}': {}", value, source)
+            }
+            Error::Version { value, message } => {
+                write!(formatter, "invalid version '{}': {}", value, message)
+            }
+            Error::Url { value, source } => {
+                write!(formatter, "invalid URL '{}': {}", value, source)
+            }
+            Error::Io(error) => write!(formatter, "I/O error: {}", error),
+            Error::Serialization(message) => write!(formatter, "serialization error: {}", message),
+            Error::Argument(message) => write!(formatter, "{}", message),
+            Error::Config(message) => write!(formatter, "invalid configuration: {}", message),
+            #[cfg(feature = "sqlite")]
+            Error::Database(error) => write!(formatter, "database error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Network(error) => Some(error),
+            Error::Date { source, .. } => Some(source),
+            Error::Url { source, .. } => Some(source),
+            Error::Io(error) => Some(error),
+            #[cfg(feature = "sqlite")]
+            Error::Database(error) => Some(error),
+            Error::Html(_)
+            | Error::Feed(_)
+            | Error::Version { .. }
+            | Error::Serialization(_)
+            | Error::Argument(_)
+            | Error::Config(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Network(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Database(error)
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_error_display() {
+    let error = Error::Html("No title found".to_string());
+    assert_eq!(error.to_string(), "HTML structure error: No title found");
+
+    let error = Error::Version {
+        value: "16.x".to_string(),
+        message: "unexpected character".to_string(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "invalid version '16.x': unexpected character"
+    );
+}
+
+#[test]
+fn test_error_source() {
+    use std::error::Error as _;
+
+    let source = chrono::NaiveDate::parse_from_str("Smarch 1, 2022", "%B %d, %Y").unwrap_err();
+    let error = Error::Date {
+        value: "Smarch 1, 2022".to_string(),
+        source,
+    };
+    assert!(error.source().is_some());
+    assert!(Error::Html(String::new()).source().is_none());
+}
@@ -0,0 +1,368 @@
+//!
+//! fetcher.rs
+//!
+
+use crate::APP_USER_AGENT;
+use crate::config::HttpConfig;
+use crate::error::{Error, Result};
+use reqwest::blocking::Response;
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Retrieves the body of a page by URL.
+///
+/// Implement this to replace the network layer, e.g. to serve pages from tests or another source.
+pub trait Fetcher {
+    /// Returns the body of the page at `url`.
+    fn fetch(&self, url: &str) -> Result<String>;
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Fetches pages over HTTP with a shared `reqwest` client.
+///
+/// Requests which fail with a connection error, a timeout or a 5xx status are retried with
+/// exponential backoff and jitter, as configured by [`HttpConfig`].
+///
+/// # Example
+///
+/// ```no_run
+/// use apple_releases::APPLE_DEV_RELEASES;
+/// use apple_releases::fetcher::{Fetcher, HttpFetcher};
+///
+/// let fetcher = HttpFetcher::new().unwrap();
+/// let body = fetcher.fetch(APPLE_DEV_RELEASES).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    client: reqwest::blocking::Client,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl HttpFetcher {
+    /// Creates a fetcher with the crate's user agent and default settings.
+    pub fn new() -> Result<Self> {
+        Self::with_config(&HttpConfig::default())
+    }
+
+    /// Creates a fetcher with the crate's user agent and the given settings.
+    ///
+    /// # Arguments
+    ///
+    /// - `config` - Timeouts, retries, proxy and extra root certificates.
+    pub fn with_config(config: &HttpConfig) -> Result<Self> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.timeout);
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        for certificate in root_certificates(config)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(Self {
+            client: builder.build()?,
+            retries: config.retries,
+            backoff: config.backoff,
+        })
+    }
+
+    /// Sends a GET request, retrying transient failures.
+    ///
+    /// # Returns
+    ///
+    /// The first response which is not a 5xx, or the last response or error once retries run out.
+    pub(crate) fn send(&self, url: &str, headers: HeaderMap) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let result = self.client.get(url).headers(headers.clone()).send();
+            let transient = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(error) => is_transient(error),
+            };
+            if !transient || attempt >= self.retries {
+                return Ok(result?);
+            }
+
+            std::thread::sleep(backoff_delay(self.backoff, attempt));
+            attempt += 1;
+        }
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    /// Returns the body of the page at `url`. Error statuses are returned as `Error::Network`.
+    fn fetch(&self, url: &str) -> Result<String> {
+        let response = self.send(url, HeaderMap::new())?.error_for_status()?;
+        Ok(response.text()?)
+    }
+}
+
+/// Reads the extra root certificates named by `config`.
+pub(crate) fn root_certificates(config: &HttpConfig) -> Result<Vec<reqwest::Certificate>> {
+    match &config.ca_bundle {
+        Some(path) => Ok(reqwest::Certificate::from_pem_bundle(&std::fs::read(
+            path,
+        )?)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Returns `true` for request errors worth retrying: failed connections and timeouts.
+pub(crate) fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
+/// Delay before retry number `attempt` (from zero): `backoff` doubled per attempt, reduced by a
+/// random amount of up to half so that clients do not retry in lockstep.
+pub(crate) fn backoff_delay(backoff: Duration, attempt: u32) -> Duration {
+    let delay = backoff.saturating_mul(2u32.saturating_pow(attempt));
+    delay.mul_f64(1.0 - random_fraction() / 2.0)
+}
+
+/// Returns a random number in `0.0..=1.0`, good enough for spreading out requests.
+pub(crate) fn random_fraction() -> f64 {
+    RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Serves a single saved page for every URL.
+///
+/// # Example
+///
+/// ```no_run
+/// use apple_releases::fetcher::{Fetcher, FileFetcher};
+///
+/// let fetcher = FileFetcher::new("releases.html");
+/// let body = fetcher.fetch("https://developer.apple.com/news/releases/").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileFetcher {
+    path: PathBuf,
+}
+
+impl FileFetcher {
+    /// Creates a fetcher which reads `path`. A path of `-` reads stdin.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Fetcher for FileFetcher {
+    fn fetch(&self, _url: &str) -> Result<String> {
+        crate::read_page(&self.path.to_string_lossy())
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Serves pages from memory, keyed by URL.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::fetcher::{Fetcher, MemoryFetcher};
+///
+/// let fetcher = MemoryFetcher::new().page("https://example.com/", "<html></html>");
+/// assert_eq!(fetcher.fetch("https://example.com/").unwrap(), "<html></html>");
+/// assert!(fetcher.fetch("https://example.com/missing").is_err());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemoryFetcher {
+    pages: HashMap<String, String>,
+}
+
+impl MemoryFetcher {
+    /// Creates a fetcher with no pages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a page served at `url`.
+    pub fn page(mut self, url: impl Into<String>, body: impl Into<String>) -> Self {
+        self.pages.insert(url.into(), body.into());
+        self
+    }
+}
+
+impl Fetcher for MemoryFetcher {
+    /// Returns the page stored for `url`, or an `Error::Io` with kind `NotFound`.
+    fn fetch(&self, url: &str) -> Result<String> {
+        self.pages.get(url).cloned().ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no page for {}", url),
+            ))
+        })
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Starts an HTTP server on a local port which answers each connection with the next of
+/// `responses`, then stops.
+///
+/// # Returns
+///
+/// The server's base URL, and a handle which joins to the raw requests received.
+#[cfg(test)]
+pub(crate) fn serve(responses: Vec<String>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            requests.push(request);
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+        requests
+    });
+
+    (url, handle)
+}
+
+/// Builds a raw HTTP response with the given status line, extra headers and body.
+#[cfg(test)]
+pub(crate) fn http_response(status: &str, headers: &[&str], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\n", status, body.len());
+    for header in headers {
+        response.push_str(header);
+        response.push_str("\r\n");
+    }
+    response.push_str("Connection: close\r\n\r\n");
+    response.push_str(body);
+    response
+}
+
+#[test]
+fn test_http_fetcher() {
+    let (url, server) = serve(vec![http_response("200 OK", &[], "<html></html>")]);
+
+    let body = HttpFetcher::new().unwrap().fetch(&url).unwrap();
+    assert_eq!(body, "<html></html>");
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET / HTTP/1.1"));
+    assert!(requests[0].contains(APP_USER_AGENT));
+}
+
+#[cfg(test)]
+fn retrying_fetcher(retries: u32) -> HttpFetcher {
+    HttpFetcher::with_config(&HttpConfig {
+        retries,
+        backoff: Duration::ZERO,
+        ..HttpConfig::default()
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_http_fetcher_error_status() {
+    let (url, server) = serve(vec![http_response("404 Not Found", &[], "")]);
+
+    let result = retrying_fetcher(3).fetch(&url);
+    assert!(matches!(result, Err(Error::Network(_))));
+    // Client errors are not retried
+    assert_eq!(server.join().unwrap().len(), 1);
+}
+
+#[test]
+fn test_http_fetcher_retries() {
+    let (url, server) = serve(vec![
+        http_response("503 Service Unavailable", &[], ""),
+        http_response("502 Bad Gateway", &[], ""),
+        http_response("200 OK", &[], "<html></html>"),
+    ]);
+
+    let body = retrying_fetcher(2).fetch(&url).unwrap();
+    assert_eq!(body, "<html></html>");
+    assert_eq!(server.join().unwrap().len(), 3);
+}
+
+#[test]
+fn test_http_fetcher_gives_up() {
+    let (url, server) = serve(vec![
+        http_response("503 Service Unavailable", &[], ""),
+        http_response("503 Service Unavailable", &[], ""),
+    ]);
+
+    let result = retrying_fetcher(1).fetch(&url);
+    assert!(matches!(result, Err(Error::Network(_))));
+    assert_eq!(server.join().unwrap().len(), 2);
+}
+
+#[test]
+fn test_http_fetcher_invalid_config() {
+    let config = HttpConfig {
+        proxy: Some("not a proxy".to_string()),
+        ..HttpConfig::default()
+    };
+    assert!(matches!(
+        HttpFetcher::with_config(&config),
+        Err(Error::Network(_))
+    ));
+
+    let config = HttpConfig {
+        ca_bundle: Some(PathBuf::from("/nonexistent/ca.pem")),
+        ..HttpConfig::default()
+    };
+    assert!(matches!(
+        HttpFetcher::with_config(&config),
+        Err(Error::Io(_))
+    ));
+}
+
+#[test]
+fn test_backoff_delay() {
+    let backoff = Duration::from_millis(100);
+    for attempt in 0..4 {
+        let delay = backoff_delay(backoff, attempt);
+        let full = backoff * 2u32.pow(attempt);
+        assert!(delay <= full && delay >= full / 2, "{:?}", delay);
+    }
+    assert_eq!(backoff_delay(Duration::ZERO, 3), Duration::ZERO);
+}
+
+#[test]
+fn test_file_fetcher() {
+    let path = std::env::temp_dir().join(format!(
+        "apple_releases-fetcher-{}.html",
+        std::process::id()
+    ));
+    std::fs::write(&path, "<html></html>").unwrap();
+
+    let body = FileFetcher::new(&path).fetch("https://example.com/");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(body.unwrap(), "<html></html>");
+}
+
+#[test]
+fn test_memory_fetcher() {
+    let fetcher = MemoryFetcher::new().page("https://example.com/", "body");
+    assert_eq!(fetcher.fetch("https://example.com/").unwrap(), "body");
+    assert!(matches!(
+        fetcher.fetch("https://example.com/other"),
+        Err(Error::Io(_))
+    ));
+}
@@ -0,0 +1,269 @@
+//!
+//! filter.rs
+//!
+
+use crate::article::Article;
+use crate::product::Product;
+use chrono::NaiveDate;
+
+/// Selects which articles are shown.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::{Filter, Product};
+///
+/// let filter = Filter::new().all(true);
+/// assert!(filter.all);
+///
+/// let filter = Filter::new()
+///     .product(Product::Xcode)
+///     .product(Product::iOS);
+/// assert_eq!(filter.products, vec![Product::Xcode, Product::iOS]);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    /// Include articles which are not recognized software releases.
+    pub all: bool,
+
+    /// Only include releases of these products. Empty means every product.
+    pub products: Vec<Product>,
+
+    /// Exclude releases of these products.
+    pub excluded_products: Vec<Product>,
+
+    /// Exclude articles published before this date.
+    pub since: Option<NaiveDate>,
+
+    /// Exclude articles published after this date.
+    pub until: Option<NaiveDate>,
+}
+
+impl Filter {
+    /// Creates a filter which only matches recognized software releases.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether articles which are not recognized software releases are included.
+    pub fn all(mut self, all: bool) -> Self {
+        self.all = all;
+        self
+    }
+
+    /// Adds `product` to the products which are included.
+    ///
+    /// Once any product is included, articles which are not releases of an included product are
+    /// filtered out, even when `all` is set.
+    pub fn product(mut self, product: Product) -> Self {
+        self.products.push(product);
+        self
+    }
+
+    /// Adds `product` to the products which are excluded.
+    pub fn exclude_product(mut self, product: Product) -> Self {
+        self.excluded_products.push(product);
+        self
+    }
+
+    /// Only includes articles published on or after `date`.
+    pub fn since(mut self, date: NaiveDate) -> Self {
+        self.since = Some(date);
+        self
+    }
+
+    /// Only includes articles published on or before `date`.
+    pub fn until(mut self, date: NaiveDate) -> Self {
+        self.until = Some(date);
+        self
+    }
+
+    /// Returns `true` if `article` passes this filter.
+    pub fn matches(&self, article: &Article) -> bool {
+        if self.since.is_some_and(|since| article.date < since)
+            || self.until.is_some_and(|until| article.date > until)
+        {
+            return false;
+        }
+
+        match &article.software_release {
+            Some(release) => {
+                let listed = |products: &[Product]| {
+                    products
+                        .iter()
+                        .any(|product| same_product(product, &release.product))
+                };
+                (self.products.is_empty() || listed(&self.products))
+                    && !listed(&self.excluded_products)
+            }
+            None => self.all && self.products.is_empty(),
+        }
+    }
+
+    /// Returns the articles which pass this filter, preserving order.
+    ///
+    /// # Arguments
+    ///
+    /// - `articles` - The articles to filter.
+    pub fn apply<'a>(&self, articles: &'a [Article]) -> Vec<&'a Article> {
+        articles
+            .iter()
+            .filter(|article| self.matches(article))
+            .collect()
+    }
+}
+
+/// Returns `true` if `a` and `b` are the same product. Names of unknown products are compared
+/// ignoring case, since they are typed on the command line.
+fn same_product(a: &Product, b: &Product) -> bool {
+    match (a, b) {
+        (Product::Other(a), Product::Other(b)) => a.eq_ignore_ascii_case(b),
+        _ => a == b,
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn test_articles() -> Vec<Article> {
+    use crate::software_release::SoftwareRelease;
+
+    let date = NaiveDate::from_ymd_opt(2022, 11, 15).unwrap();
+    vec![
+        Article {
+            title: "iOS 16.2 beta 3 (20C5049e)".to_string(),
+            software_release: SoftwareRelease::software_release(
+                "iOS 16.2 beta 3 (20C5049e)".to_string(),
+            ),
+            date,
+            release_notes_url: None,
+            permalink: None,
+            links: Vec::new(),
+            title_html: None,
+            body: None,
+        },
+        Article {
+            title: "Xcode 14.1 (14B47b)".to_string(),
+            software_release: SoftwareRelease::software_release("Xcode 14.1 (14B47b)".to_string()),
+            date,
+            release_notes_url: None,
+            permalink: None,
+            links: Vec::new(),
+            title_html: None,
+            body: None,
+        },
+        Article {
+            title: "Upcoming requirements for app distribution".to_string(),
+            software_release: None,
+            date,
+            release_notes_url: None,
+            permalink: None,
+            links: Vec::new(),
+            title_html: None,
+            body: None,
+        },
+    ]
+}
+
+#[test]
+fn test_filter_releases_only() {
+    let articles = test_articles();
+    let filtered = Filter::new().apply(&articles);
+    assert_eq!(filtered.len(), 2);
+    assert_eq!(filtered[0].title, "iOS 16.2 beta 3 (20C5049e)");
+}
+
+#[test]
+fn test_filter_all() {
+    let articles = test_articles();
+    let filtered = Filter::new().all(true).apply(&articles);
+    assert_eq!(filtered.len(), 3);
+}
+
+#[test]
+fn test_filter_products() {
+    let articles = test_articles();
+    let filtered = Filter::new()
+        .all(true)
+        .product(Product::Xcode)
+        .apply(&articles);
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].title, "Xcode 14.1 (14B47b)");
+
+    let filtered = Filter::new()
+        .product(Product::Xcode)
+        .product(Product::iOS)
+        .apply(&articles);
+    assert_eq!(filtered.len(), 2);
+}
+
+#[test]
+fn test_filter_other_products() {
+    use crate::software_release::SoftwareRelease;
+
+    let mut articles = test_articles();
+    let title = "Final Cut Pro 11 beta 2 (24A5279h)";
+    articles[2].title = title.to_string();
+    articles[2].software_release = SoftwareRelease::software_release(title.to_string());
+
+    let filtered = Filter::new()
+        .product(Product::from_name("final cut pro"))
+        .apply(&articles);
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].title, title);
+
+    let filtered = Filter::new()
+        .exclude_product(Product::from_name("Final Cut Pro"))
+        .apply(&articles);
+    assert_eq!(filtered.len(), 2);
+}
+
+#[test]
+fn test_filter_excluded_products() {
+    let articles = test_articles();
+    let filtered = Filter::new()
+        .all(true)
+        .exclude_product(Product::iOS)
+        .apply(&articles);
+    let titles: Vec<&str> = filtered.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Xcode 14.1 (14B47b)",
+            "Upcoming requirements for app distribution"
+        ]
+    );
+}
+
+#[test]
+fn test_filter_date_range() {
+    let mut articles = test_articles();
+    articles[0].date = NaiveDate::from_ymd_opt(2022, 11, 1).unwrap();
+    articles[2].date = NaiveDate::from_ymd_opt(2022, 12, 1).unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2022, 11, 15).unwrap();
+    let filtered = Filter::new().all(true).since(date).apply(&articles);
+    let titles: Vec<&str> = filtered.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Xcode 14.1 (14B47b)",
+            "Upcoming requirements for app distribution"
+        ]
+    );
+
+    let filtered = Filter::new().all(true).until(date).apply(&articles);
+    let titles: Vec<&str> = filtered.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        vec!["iOS 16.2 beta 3 (20C5049e)", "Xcode 14.1 (14B47b)"]
+    );
+
+    let filtered = Filter::new()
+        .all(true)
+        .since(date)
+        .until(date)
+        .apply(&articles);
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].title, "Xcode 14.1 (14B47b)");
+}
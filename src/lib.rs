@@ -0,0 +1,470 @@
+//!
+//! lib.rs
+//!
+//! Library for fetching and parsing the Apple Developer [Software Releases] page.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use apple_releases::Filter;
+//!
+//! let result = apple_releases::fetch_articles().unwrap();
+//! for article in Filter::new().apply(&result.articles) {
+//!     println!("{}", article);
+//! }
+//! ```
+//!
+//! [Software Releases]: https://developer.apple.com/news/releases/
+
+#[cfg(feature = "async")]
+use crate::async_fetcher::AsyncHttpFetcher;
+use crate::fetcher::{Fetcher, HttpFetcher};
+use crate::parse::{ParseResult, ParseWarning};
+use crate::selectors::Selectors;
+use ::url::Url;
+use lazy_static::lazy_static;
+use std::io::Read;
+
+pub mod apple_build;
+pub mod article;
+#[cfg(feature = "async")]
+pub mod async_fetcher;
+pub mod body;
+pub mod cache;
+pub mod config;
+pub mod crawl;
+pub mod date_spec;
+pub mod error;
+pub mod fetcher;
+pub mod filter;
+pub mod link;
+pub mod output;
+pub mod parse;
+pub mod product;
+pub mod release_stage;
+pub mod rss;
+pub mod selectors;
+pub mod software_release;
+pub mod source;
+pub mod state;
+#[cfg(feature = "sqlite")]
+pub mod store;
+mod title;
+pub mod url;
+pub mod watch;
+
+pub use apple_build::AppleBuild;
+pub use article::Article;
+pub use error::{Error, Result};
+pub use filter::Filter;
+pub use output::OutputFormat;
+pub use product::Product;
+pub use release_stage::ReleaseStage;
+pub use software_release::SoftwareRelease;
+pub use source::Source;
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// User agent for network requests.
+pub const APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Webpage listing new software releases.
+pub const APPLE_DEV_RELEASES: &str = "https://developer.apple.com/news/releases/";
+
+lazy_static! {
+    pub(crate) static ref SELECTORS: Selectors = Selectors::new();
+    pub(crate) static ref RELEASES_PAGE: Url = Url::parse(APPLE_DEV_RELEASES).unwrap();
+    pub(crate) static ref RELEASES_FEED: Url = Url::parse(rss::APPLE_DEV_RELEASES_RSS).unwrap();
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Fetches the raw HTML of the Apple Developer releases page.
+///
+/// # Example
+///
+/// ```no_run
+/// let html = apple_releases::fetch_page().unwrap();
+/// std::fs::write("releases.html", html).unwrap();
+/// ```
+pub fn fetch_page() -> Result<String> {
+    HttpFetcher::new()?.fetch(APPLE_DEV_RELEASES)
+}
+
+/// Fetches the Apple Developer releases page and parses its articles.
+///
+/// # Returns
+///
+/// The articles, in the order they appear on the page, along with warnings for any articles which
+/// could not be parsed.
+///
+/// # Example
+///
+/// ```no_run
+/// let result = apple_releases::fetch_articles().unwrap();
+/// assert!(!result.articles.is_empty());
+/// ```
+pub fn fetch_articles() -> Result<ParseResult> {
+    fetch_articles_from(&HttpFetcher::new()?, APPLE_DEV_RELEASES)
+}
+
+/// Fetches a releases page with `fetcher` and parses its articles.
+///
+/// # Arguments
+///
+/// - `fetcher` - Retrieves the page.
+/// - `url` - URL of the releases page, usually [`APPLE_DEV_RELEASES`].
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::APPLE_DEV_RELEASES;
+/// use apple_releases::fetcher::MemoryFetcher;
+///
+/// let html = r#"
+/// <section class="article-content-container">
+///     <a class="article-title" href="/download/"><h2>iOS 16.1.2 (20B110)</h2></a>
+///     <p class="article-date">November 30, 2022</p>
+/// </section>
+/// "#;
+/// let fetcher = MemoryFetcher::new().page(APPLE_DEV_RELEASES, html);
+///
+/// let result = apple_releases::fetch_articles_from(&fetcher, APPLE_DEV_RELEASES).unwrap();
+/// assert_eq!(result.articles.len(), 1);
+/// ```
+pub fn fetch_articles_from(fetcher: &dyn Fetcher, url: &str) -> Result<ParseResult> {
+    let page_url = crate::url::page_url(url)?;
+    Ok(parse::parse_articles_with(
+        fetcher.fetch(url)?,
+        &SELECTORS,
+        &page_url,
+    ))
+}
+
+/// Fetches the Apple Developer releases page and parses its articles, without blocking.
+///
+/// Available with the `async` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// let result = apple_releases::fetch_articles_async().await.unwrap();
+/// assert!(!result.articles.is_empty());
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn fetch_articles_async() -> Result<ParseResult> {
+    fetch_articles_from_async(&AsyncHttpFetcher::new()?, APPLE_DEV_RELEASES).await
+}
+
+/// Fetches a releases page with `fetcher` and parses its articles, without blocking.
+///
+/// Available with the `async` feature.
+///
+/// # Arguments
+///
+/// - `fetcher` - Retrieves the page.
+/// - `url` - URL of the releases page, usually [`APPLE_DEV_RELEASES`].
+#[cfg(feature = "async")]
+pub async fn fetch_articles_from_async(
+    fetcher: &AsyncHttpFetcher,
+    url: &str,
+) -> Result<ParseResult> {
+    let page_url = crate::url::page_url(url)?;
+    Ok(parse::parse_articles_with(
+        fetcher.fetch(url).await?,
+        &SELECTORS,
+        &page_url,
+    ))
+}
+
+/// Fetches articles from the releases page, the RSS feed, or both, with `fetcher`.
+///
+/// # Arguments
+///
+/// - `fetcher` - Retrieves the page and feed.
+/// - `source` - Which of them to read. With [`Source::Auto`], the feed is only fetched if no
+///   articles are found on the page, e.g. because Apple changed its markup. If the feed also fails,
+///   the page's (empty) result is returned with the feed error as a warning.
+/// - `page_url` - URL of the releases page, usually [`APPLE_DEV_RELEASES`].
+/// - `feed_url` - URL of the RSS feed, usually [`rss::APPLE_DEV_RELEASES_RSS`].
+/// - `selectors` - Where to find articles on the page, usually [`Selectors::default`].
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::fetcher::MemoryFetcher;
+/// use apple_releases::rss::APPLE_DEV_RELEASES_RSS;
+/// use apple_releases::selectors::Selectors;
+/// use apple_releases::{APPLE_DEV_RELEASES, Source};
+///
+/// let feed = r#"<rss version="2.0"><channel><item>
+///     <title>iOS 16.1.2 (20B110)</title>
+///     <pubDate>Wed, 30 Nov 2022 10:00:00 PST</pubDate>
+/// </item></channel></rss>"#;
+/// let fetcher = MemoryFetcher::new()
+///     .page(APPLE_DEV_RELEASES, "<html><body>Redesigned!</body></html>")
+///     .page(APPLE_DEV_RELEASES_RSS, feed);
+///
+/// let result = apple_releases::fetch_articles_from_source(
+///     &fetcher,
+///     Source::Auto,
+///     APPLE_DEV_RELEASES,
+///     APPLE_DEV_RELEASES_RSS,
+///     &Selectors::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(result.articles[0].title, "iOS 16.1.2 (20B110)");
+/// ```
+pub fn fetch_articles_from_source(
+    fetcher: &dyn Fetcher,
+    source: Source,
+    page_url: &str,
+    feed_url: &str,
+    selectors: &Selectors,
+) -> Result<ParseResult> {
+    match source {
+        Source::Html => Ok(parse::parse_articles_with(
+            fetcher.fetch(page_url)?,
+            selectors,
+            &crate::url::page_url(page_url)?,
+        )),
+        Source::Rss => {
+            rss::parse_feed_with(fetcher.fetch(feed_url)?, &crate::url::page_url(feed_url)?)
+        }
+        Source::Auto => {
+            let page = fetcher.fetch(page_url)?;
+            if rss::is_feed(&page) {
+                return rss::parse_feed_with(page, &crate::url::page_url(page_url)?);
+            }
+
+            let mut result =
+                parse::parse_articles_with(page, selectors, &crate::url::page_url(page_url)?);
+            if !result.articles.is_empty() {
+                return Ok(result);
+            }
+
+            let feed = crate::url::page_url(feed_url).and_then(|url| {
+                fetcher
+                    .fetch(feed_url)
+                    .and_then(|feed| rss::parse_feed_with(feed, &url))
+            });
+            match feed {
+                Ok(mut feed) => {
+                    result.warnings.append(&mut feed.warnings);
+                    feed.warnings = result.warnings;
+                    Ok(feed)
+                }
+                Err(error) => {
+                    result.warnings.push(ParseWarning {
+                        title: None,
+                        html: String::new(),
+                        error,
+                    });
+                    Ok(result)
+                }
+            }
+        }
+    }
+}
+
+/// Reads a saved releases page.
+///
+/// # Arguments
+///
+/// - `input` - Path of the saved page, or `-` to read from stdin.
+pub fn read_page(input: &str) -> Result<String> {
+    if input == "-" {
+        let mut body = String::new();
+        std::io::stdin().read_to_string(&mut body)?;
+        Ok(body)
+    } else {
+        Ok(std::fs::read_to_string(input)?)
+    }
+}
+
+/// Writes `contents` to a temporary file and renames it over `path`, so readers never see a
+/// partially written file.
+///
+/// The temporary file is named after the process and a counter, so concurrent writers, such as
+/// several cron jobs sharing a cache, never write to the same one.
+pub(crate) fn write_atomic(path: &std::path::Path, contents: &str) -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = std::fs::write(&temp, contents).and_then(|()| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    Ok(result?)
+}
+
+/// Reads a saved releases page and parses its articles.
+///
+/// # Arguments
+///
+/// - `input` - Path of the saved page, or `-` to read from stdin.
+///
+/// # Example
+///
+/// ```no_run
+/// let result = apple_releases::read_articles("releases.html").unwrap();
+/// println!("{} articles", result.articles.len());
+/// ```
+pub fn read_articles(input: &str) -> Result<ParseResult> {
+    Ok(parse::parse_articles(read_page(input)?))
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_read_articles() {
+    let path = std::env::temp_dir().join(format!("apple_releases-{}.html", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>iOS 16.1.2 (20B110)</h2></a>
+    <p class="article-date">November 30, 2022</p>
+</section>
+        "#,
+    )
+    .unwrap();
+
+    let result = read_articles(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    let result = result.unwrap();
+    assert_eq!(result.articles.len(), 1);
+    assert_eq!(result.articles[0].title, "iOS 16.1.2 (20B110)");
+}
+
+#[test]
+fn test_fetch_articles_from_http() {
+    let html = r#"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>Xcode 14.1 (14B47b)</h2></a>
+    <p class="article-date">November 1, 2022</p>
+</section>
+    "#;
+    let (url, server) = fetcher::serve(vec![fetcher::http_response("200 OK", &[], html)]);
+
+    let result = fetch_articles_from(&HttpFetcher::new().unwrap(), &url).unwrap();
+    assert_eq!(result.articles.len(), 1);
+    assert_eq!(result.articles[0].title, "Xcode 14.1 (14B47b)");
+    server.join().unwrap();
+}
+
+#[test]
+fn test_read_articles_missing_file() {
+    let result = read_articles("/nonexistent/releases.html");
+    assert!(matches!(result, Err(Error::Io(_))));
+}
+
+#[cfg(feature = "async")]
+#[cfg(test)]
+#[tokio::test]
+async fn test_fetch_articles_from_async() {
+    let html = r#"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>Xcode 14.1 (14B47b)</h2></a>
+    <p class="article-date">November 1, 2022</p>
+</section>
+    "#;
+    let (url, server) = fetcher::serve(vec![
+        fetcher::http_response("200 OK", &[], html),
+        fetcher::http_response("200 OK", &[], html),
+    ]);
+
+    let fetcher = AsyncHttpFetcher::new().unwrap();
+    let (first, second) = tokio::join!(
+        fetch_articles_from_async(&fetcher, &url),
+        fetch_articles_from_async(&fetcher, &url),
+    );
+    assert_eq!(first.unwrap().articles[0].title, "Xcode 14.1 (14B47b)");
+    assert_eq!(second.unwrap().articles.len(), 1);
+    server.join().unwrap();
+}
+
+#[test]
+fn test_fetch_articles_from_source() {
+    use crate::fetcher::MemoryFetcher;
+
+    let page = r#"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>Xcode 14.1 (14B47b)</h2></a>
+    <p class="article-date">November 1, 2022</p>
+</section>
+    "#;
+    let feed = r#"<?xml version="1.0"?><rss version="2.0"><channel><item>
+    <title>iOS 16.1.2 (20B110)</title>
+    <pubDate>Wed, 30 Nov 2022 10:00:00 PST</pubDate>
+</item></channel></rss>"#;
+    let fetch = |fetcher: &MemoryFetcher, source| {
+        fetch_articles_from_source(
+            fetcher,
+            source,
+            "https://page/",
+            "https://feed/",
+            &SELECTORS,
+        )
+    };
+
+    let fetcher = MemoryFetcher::new()
+        .page("https://page/", page)
+        .page("https://feed/", feed);
+    let title = |result: Result<ParseResult>| result.unwrap().articles[0].title.clone();
+    assert_eq!(title(fetch(&fetcher, Source::Html)), "Xcode 14.1 (14B47b)");
+    assert_eq!(title(fetch(&fetcher, Source::Rss)), "iOS 16.1.2 (20B110)");
+    assert_eq!(title(fetch(&fetcher, Source::Auto)), "Xcode 14.1 (14B47b)");
+
+    // Falls back to the feed when the page has no articles
+    let fetcher = MemoryFetcher::new()
+        .page("https://page/", "<html></html>")
+        .page("https://feed/", feed);
+    assert_eq!(title(fetch(&fetcher, Source::Auto)), "iOS 16.1.2 (20B110)");
+    assert!(fetch(&fetcher, Source::Html).unwrap().articles.is_empty());
+
+    // A page which is a feed, e.g. a saved feed passed to --input
+    let fetcher = MemoryFetcher::new().page("https://page/", feed);
+    assert_eq!(title(fetch(&fetcher, Source::Auto)), "iOS 16.1.2 (20B110)");
+
+    // A failed fallback is reported as a warning
+    let fetcher = MemoryFetcher::new().page("https://page/", "<html></html>");
+    let result = fetch(&fetcher, Source::Auto).unwrap();
+    assert!(result.articles.is_empty());
+    assert!(matches!(result.warnings[0].error, Error::Io(_)));
+}
+
+#[test]
+fn test_write_atomic_concurrently() {
+    let dir = std::env::temp_dir().join(format!("apple_releases-atomic-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("state.json");
+
+    let contents: Vec<String> = (0..8)
+        .map(|index| index.to_string().repeat(10_000))
+        .collect();
+    std::thread::scope(|scope| {
+        for contents in &contents {
+            let path = &path;
+            scope.spawn(move || write_atomic(path, contents).unwrap());
+        }
+    });
+
+    // One complete write wins, and no temporary files are left behind
+    assert!(contents.contains(&std::fs::read_to_string(&path).unwrap()));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,270 @@
+//!
+//! link.rs
+//!
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use url::Url;
+
+/// What an article link points to, judged from its text and URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LinkKind {
+    /// Release notes, e.g. `/go/?id=xcode-14-sdk-rn`.
+    ReleaseNotes,
+
+    /// A download page, e.g. `/download/applications`.
+    Downloads,
+
+    /// Developer documentation, e.g. "What's new" pages.
+    Documentation,
+
+    /// An App Store listing, e.g. for Transporter.
+    AppStore,
+
+    /// Anything else.
+    Other,
+}
+
+impl LinkKind {
+    /// Classifies a link by its text and URL.
+    ///
+    /// # Arguments
+    ///
+    /// - `text` - The link text, e.g. `View release notes`.
+    /// - `url` - Where the link points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apple_releases::link::LinkKind;
+    /// use url::Url;
+    ///
+    /// let url = Url::parse("https://developer.apple.com/go/?id=xcode-14-sdk-rn").unwrap();
+    /// assert_eq!(LinkKind::classify("View release notes", &url), LinkKind::ReleaseNotes);
+    /// ```
+    pub fn classify(text: &str, url: &Url) -> LinkKind {
+        let text = text.to_lowercase();
+        let host = url.host_str().unwrap_or_default();
+        let path = url.path();
+        let id = url
+            .query_pairs()
+            .find(|(name, _)| name == "id")
+            .map(|(_, id)| id.to_lowercase())
+            .unwrap_or_default();
+
+        if host == "apps.apple.com" || host == "itunes.apple.com" || text.contains("app store") {
+            LinkKind::AppStore
+        } else if text.contains("release notes") || id.ends_with("-rn") {
+            LinkKind::ReleaseNotes
+        } else if text.contains("download") || path.starts_with("/download") {
+            LinkKind::Downloads
+        } else if text.contains("documentation")
+            || text.contains("what's new")
+            || text.contains("what’s new")
+            || path.starts_with("/documentation")
+        {
+            LinkKind::Documentation
+        } else {
+            LinkKind::Other
+        }
+    }
+}
+
+impl Display for LinkKind {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LinkKind::ReleaseNotes => "release notes",
+            LinkKind::Downloads => "downloads",
+            LinkKind::Documentation => "documentation",
+            LinkKind::AppStore => "App Store",
+            LinkKind::Other => "other",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// A link from the body of an article.
+///
+/// # JSON
+///
+/// ```json
+/// {
+///   "kind": "ReleaseNotes",
+///   "text": "View release notes",
+///   "url": "https://developer.apple.com/go/?id=xcode-14-sdk-rn"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    /// What the link points to.
+    pub kind: LinkKind,
+
+    /// The link text with whitespace collapsed.
+    pub text: String,
+
+    /// The absolute URL.
+    pub url: Url,
+}
+
+impl Link {
+    /// Creates a link, classifying it with [`LinkKind::classify`].
+    ///
+    /// # Arguments
+    ///
+    /// - `text` - The link text. Runs of whitespace are collapsed to single spaces.
+    /// - `url` - Where the link points.
+    pub fn new(text: &str, url: Url) -> Self {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        Self {
+            kind: LinkKind::classify(&text, &url),
+            text,
+            url,
+        }
+    }
+}
+
+/// Returns the URL of the first release notes link on the same host as the page the links were
+/// found on, preferring short links like `/go/?id=ios-16.2-rn`.
+///
+/// # Arguments
+///
+/// - `links` - The article's links.
+/// - `page_url` - URL of the page, usually on developer.apple.com, or a stand-in server.
+pub(crate) fn release_notes_url(links: &[Link], page_url: &Url) -> Option<Url> {
+    let release_notes = || {
+        links.iter().filter(|link| {
+            link.kind == LinkKind::ReleaseNotes && link.url.host_str() == page_url.host_str()
+        })
+    };
+    let short_link = |link: &&Link| {
+        link.url.path() == "/go/"
+            && link
+                .url
+                .query_pairs()
+                .any(|(name, id)| name == "id" && id.ends_with("-rn"))
+    };
+
+    release_notes()
+        .find(short_link)
+        .or_else(|| release_notes().next())
+        .map(|link| link.url.clone())
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_classify() {
+    let kind = |text: &str, url: &str| LinkKind::classify(text, &Url::parse(url).unwrap());
+
+    assert_eq!(
+        kind(
+            "View release notes",
+            "https://developer.apple.com/go/?id=ios-16.2-rn"
+        ),
+        LinkKind::ReleaseNotes
+    );
+    assert_eq!(
+        kind(
+            "Learn more",
+            "https://developer.apple.com/go/?id=xcode-14-sdk-rn"
+        ),
+        LinkKind::ReleaseNotes
+    );
+    assert_eq!(
+        kind(
+            "View downloads",
+            "https://developer.apple.com/download/applications"
+        ),
+        LinkKind::Downloads
+    );
+    assert_eq!(
+        kind(
+            "What’s new in Xcode",
+            "https://developer.apple.com/xcode/whats-new/"
+        ),
+        LinkKind::Documentation
+    );
+    assert_eq!(
+        kind(
+            "Learn more",
+            "https://developer.apple.com/documentation/xcode"
+        ),
+        LinkKind::Documentation
+    );
+    assert_eq!(
+        kind(
+            "View on the Mac App Store",
+            "https://apps.apple.com/app/id1450874784"
+        ),
+        LinkKind::AppStore
+    );
+    assert_eq!(
+        kind(
+            "Learn more",
+            "https://apps.apple.com/us/app/transporter/id1450874784"
+        ),
+        LinkKind::AppStore
+    );
+    assert_eq!(
+        kind(
+            "Apple Developer Forums",
+            "https://developer.apple.com/forums/"
+        ),
+        LinkKind::Other
+    );
+}
+
+#[test]
+fn test_link_new() {
+    let link = Link::new(
+        "\n  View   release\n notes ",
+        Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").unwrap(),
+    );
+    assert_eq!(link.text, "View release notes");
+    assert_eq!(link.kind, LinkKind::ReleaseNotes);
+}
+
+#[test]
+fn test_release_notes_url() {
+    let links = vec![
+        Link::new(
+            "View downloads",
+            Url::parse("https://developer.apple.com/download/applications").unwrap(),
+        ),
+        Link::new(
+            "Release notes",
+            Url::parse("https://help.apple.com/itc/transporteruserguide/").unwrap(),
+        ),
+        Link::new(
+            "View release notes",
+            Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").unwrap(),
+        ),
+    ];
+    let page_url = Url::parse("https://developer.apple.com/news/releases/").unwrap();
+    assert_eq!(
+        release_notes_url(&links, &page_url).unwrap().as_str(),
+        "https://developer.apple.com/go/?id=ios-16.2-rn"
+    );
+    assert_eq!(release_notes_url(&links[..2], &page_url), None);
+
+    // A stand-in server's own links
+    let links = vec![
+        Link::new(
+            "Xcode release notes",
+            Url::parse("http://localhost:8080/documentation/xcode-release-notes/").unwrap(),
+        ),
+        Link::new(
+            "View release notes",
+            Url::parse("http://localhost:8080/go/?id=ios-16.2-rn").unwrap(),
+        ),
+    ];
+    let page_url = Url::parse("http://localhost:8080/news/releases/").unwrap();
+    assert_eq!(
+        release_notes_url(&links, &page_url).unwrap().as_str(),
+        "http://localhost:8080/go/?id=ios-16.2-rn"
+    );
+    assert_eq!(
+        release_notes_url(&links[..1], &page_url),
+        Some(links[0].url.clone())
+    );
+}
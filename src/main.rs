@@ -3,59 +3,362 @@
 //!
 
 use crate::cli::cli;
-use crate::selectors::Selectors;
-use lazy_static::lazy_static;
-use std::string::ToString;
+use apple_releases::cache::{CacheMode, CachingFetcher};
+use apple_releases::config::{Config, HttpConfig};
+use apple_releases::crawl::Crawler;
+use apple_releases::fetcher::{Fetcher, FileFetcher, HttpFetcher};
+use apple_releases::output::write_articles;
+use apple_releases::selectors::Selectors;
+use apple_releases::state::SeenState;
+use apple_releases::watch::{self, WatchEvent};
+use apple_releases::{Article, Filter, OutputFormat, Product, Source};
+use chrono::NaiveDate;
+use clap::ArgMatches;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
 
-mod article;
 mod cli;
-mod parse;
-mod product;
-mod selectors;
-mod software_release;
-mod url;
 
 /* ---------------------------------------------------------------------------------------------- */
 
-type GenericError = Box<dyn std::error::Error + Send + Sync + 'static>;
-type GenericResult<T> = Result<T, GenericError>;
+/// Exit status of `new` when there are no new articles.
+const NOTHING_NEW: u8 = 3;
 
-/// User agent for network requests.
-const APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+/// Executable entry point.
+fn main() -> ExitCode {
+    let args = cli().get_matches();
 
-/// Webpage listing new software releases.
-const APPLE_DEV_RELEASES: &str = "https://developer.apple.com/news/releases/";
+    let result = match args.subcommand() {
+        Some(("fetch", fetch_args)) => fetch(fetch_args).map(|()| ExitCode::SUCCESS),
+        Some(("backfill", backfill_args)) => cli::check_backfill(&args, backfill_args)
+            .and_then(|()| backfill(backfill_args))
+            .map(|()| ExitCode::SUCCESS),
+        Some(("new", new_args)) => new(new_args),
+        Some(("watch", watch_args)) => watch(watch_args).map(|()| ExitCode::SUCCESS),
+        _ => list(&args).map(|()| ExitCode::SUCCESS),
+    };
 
-lazy_static! {
-    static ref SELECTORS: Selectors = Selectors::new();
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
 }
 
-/* ---------------------------------------------------------------------------------------------- */
+/// Downloads the releases page, or the feed with `--source rss`, and saves it for later use with
+/// `--input`.
+fn fetch(args: &ArgMatches) -> apple_releases::Result<()> {
+    let path = args.get_one::<String>("save").unwrap();
+    let source: Source = args.get_one::<String>("source").unwrap().parse()?;
+    let url = match source {
+        Source::Rss => args.get_one::<String>("feed-url").unwrap(),
+        Source::Html | Source::Auto => args.get_one::<String>("base-url").unwrap(),
+    };
+    let body = http_fetcher(args)?.fetch(url)?;
+    std::fs::write(path, body)?;
+    Ok(())
+}
 
-/// Executable entry point.
-fn main() {
-    let args = cli().get_matches();
-    let show_all = args.get_one::<bool>("all").unwrap();
-
-    let body = url::get(APPLE_DEV_RELEASES.to_string()).unwrap();
-
-    let articles = parse::parse_articles(body).unwrap();
-
-    articles
-        .iter()
-        .for_each(|article| match article.software_release {
-            Some(_) => {
-                println!("{}", article);
-                // if *unfurl_urls {
-                //     println!(" - {}", article.release_notes_url_unfurled().unwrap());
-                // } else {
-                //     println!(" - {}", article.release_notes_url.as_ref().unwrap());
-                // }
-            }
-            _ => {
-                if *show_all {
-                    println!("{}", article);
+/// Crawls the releases page and the older pages it links to, printing every article.
+fn backfill(args: &ArgMatches) -> apple_releases::Result<()> {
+    let format: OutputFormat = args.get_one::<String>("output").unwrap().parse()?;
+    let base_url = args.get_one::<String>("base-url").unwrap();
+
+    let mut crawler = Crawler::new()
+        .delay(*args.get_one::<Duration>("delay").unwrap())
+        .selectors(selectors(args)?);
+    if let Some(max_pages) = args.get_one::<usize>("max-pages") {
+        crawler = crawler.max_pages(*max_pages);
+    }
+    let result = crawler.crawl(http_fetcher(args)?.as_ref(), base_url)?;
+
+    for warning in &result.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    let articles = history(args, result.articles)?;
+    let articles: Vec<&Article> = articles.iter().collect();
+    write_articles(std::io::stdout().lock(), format, &articles)
+}
+
+/// With `--history` or `--db`, stores `articles` and returns every article stored so far.
+#[cfg(feature = "sqlite")]
+fn history(args: &ArgMatches, articles: Vec<Article>) -> apple_releases::Result<Vec<Article>> {
+    match record(args, &articles)? {
+        Some(store) => store.articles(),
+        None => Ok(articles),
+    }
+}
+
+/// With `--history` or `--db`, stores `articles`, returning the store they were written to.
+#[cfg(feature = "sqlite")]
+fn record(
+    args: &ArgMatches,
+    articles: &[Article],
+) -> apple_releases::Result<Option<apple_releases::store::Store>> {
+    use apple_releases::store::Store;
+
+    let path = match args.get_one::<String>("db") {
+        Some(path) => PathBuf::from(path),
+        None if args.get_flag("history") => Store::default_path().ok_or_else(|| {
+            apple_releases::Error::Argument("no data directory found; use --db".to_string())
+        })?,
+        None => return Ok(None),
+    };
+
+    let mut store = Store::open(&path)?;
+    store.upsert(articles, chrono::Utc::now())?;
+    Ok(Some(store))
+}
+
+/// Returns `articles`; storing history requires the `sqlite` feature.
+#[cfg(not(feature = "sqlite"))]
+fn history(_args: &ArgMatches, articles: Vec<Article>) -> apple_releases::Result<Vec<Article>> {
+    Ok(articles)
+}
+
+/// Does nothing; storing history requires the `sqlite` feature.
+#[cfg(not(feature = "sqlite"))]
+fn record(_args: &ArgMatches, _articles: &[Article]) -> apple_releases::Result<Option<()>> {
+    Ok(None)
+}
+
+/// Reads the configuration file named by `--config`, or the default one if present.
+fn config(args: &ArgMatches) -> apple_releases::Result<Config> {
+    match args.get_one::<String>("config") {
+        Some(path) => Config::load(Path::new(path)),
+        None => Config::load_default(),
+    }
+}
+
+/// Network settings from the configuration file, overridden by command line flags.
+fn http_config(args: &ArgMatches) -> apple_releases::Result<HttpConfig> {
+    let mut http = config(args)?.http;
+    if let Some(timeout) = args.get_one::<Duration>("connect-timeout") {
+        http.connect_timeout = *timeout;
+    }
+    if let Some(timeout) = args.get_one::<Duration>("timeout") {
+        http.timeout = *timeout;
+    }
+    if let Some(retries) = args.get_one::<u32>("retries") {
+        http.retries = *retries;
+    }
+    if let Some(proxy) = args.get_one::<String>("proxy") {
+        http.proxy = Some(proxy.clone());
+    }
+    if let Some(path) = args.get_one::<String>("ca-bundle") {
+        http.ca_bundle = Some(PathBuf::from(path));
+    }
+    Ok(http)
+}
+
+/// Page selectors from the configuration file, with `--selector` flags tried first. Fails if any
+/// selector is invalid, so mistakes are reported before fetching anything.
+fn selectors(args: &ArgMatches) -> apple_releases::Result<Selectors> {
+    let mut config = config(args)?.selectors;
+    let overrides: Vec<&(String, String)> = args
+        .get_many::<(String, String)>("selector")
+        .into_iter()
+        .flatten()
+        .collect();
+    for (field, selector) in overrides.into_iter().rev() {
+        config.prepend(field, selector.clone())?;
+    }
+    Selectors::with_config(&config)
+}
+
+/// Creates a fetcher for network requests, honoring the cache and network flags.
+fn http_fetcher(args: &ArgMatches) -> apple_releases::Result<Box<dyn Fetcher>> {
+    let http = HttpFetcher::with_config(&http_config(args)?)?;
+    let cache_dir = CachingFetcher::default_dir();
+    if args.get_flag("no-cache") || cache_dir.is_none() {
+        return Ok(Box::new(http));
+    }
+
+    let mode = if args.get_flag("refresh") {
+        CacheMode::Refresh
+    } else {
+        CacheMode::Normal
+    };
+    Ok(Box::new(CachingFetcher::with_http(
+        http,
+        cache_dir.unwrap(),
+        mode,
+    )))
+}
+
+/// Prints the filtered list of articles.
+fn list(args: &ArgMatches) -> apple_releases::Result<()> {
+    let format: OutputFormat = args.get_one::<String>("output").unwrap().parse()?;
+
+    let articles = history(args, fetch_articles(args, &selectors(args)?)?)?;
+    let articles = filter(args).apply(&articles);
+    write_articles(std::io::stdout().lock(), format, &articles)
+}
+
+/// Prints the filtered articles not seen by a previous run, then records them as seen.
+///
+/// # Returns
+///
+/// Success if anything was printed, or `NOTHING_NEW`.
+fn new(args: &ArgMatches) -> apple_releases::Result<ExitCode> {
+    let format: OutputFormat = args.get_one::<String>("output").unwrap().parse()?;
+    let path = match args.get_one::<String>("state") {
+        Some(path) => PathBuf::from(path),
+        None => SeenState::default_path().ok_or_else(|| {
+            apple_releases::Error::Argument("no data directory found; use --state".to_string())
+        })?,
+    };
+
+    let articles = fetch_articles(args, &selectors(args)?)?;
+    record(args, &articles)?;
+    let articles = filter(args).apply(&articles);
+
+    let mut state = SeenState::load(&path)?;
+    let unseen = state.unseen(articles.iter().copied());
+    if unseen.is_empty() {
+        return Ok(ExitCode::from(NOTHING_NEW));
+    }
+
+    write_articles(std::io::stdout().lock(), format, &unseen)?;
+    state.mark_seen(unseen);
+    state.save(&path)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Polls for articles until interrupted, printing an event and running `--exec` for each new one.
+///
+/// With `--state`, articles seen by earlier runs are remembered; otherwise the first poll only
+/// records what is already there. With `--history` or `--db`, every poll is stored. Errors while
+/// polling are printed and the next poll goes ahead.
+fn watch(args: &ArgMatches) -> apple_releases::Result<()> {
+    let interval = *args.get_one::<Duration>("interval").unwrap();
+    let hook = args.get_one::<String>("exec");
+    let path = args.get_one::<String>("state").map(PathBuf::from);
+
+    let (stop, stopped) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        let _ = stop.send(());
+    })
+    .map_err(|error| apple_releases::Error::Io(std::io::Error::other(error)))?;
+
+    let mut state = match &path {
+        Some(path) => SeenState::load(path)?,
+        None => SeenState::default(),
+    };
+    let mut first = path.is_none();
+    let filter = filter(args);
+    let selectors = selectors(args)?;
+
+    loop {
+        match fetch_articles(args, &selectors) {
+            Ok(articles) => {
+                if let Err(error) = record(args, &articles) {
+                    eprintln!("Warning: {}", error);
+                }
+                let articles = filter.apply(&articles);
+                let unseen = if first {
+                    Vec::new()
+                } else {
+                    state.unseen(articles.iter().copied())
+                };
+
+                // Oldest first, so events are in the order the articles were published
+                for article in unseen.iter().rev() {
+                    emit(article, hook)?;
+                }
+                state.mark_seen(articles);
+                if let Some(path) = &path {
+                    state.save(path)?;
                 }
+                first = false;
+            }
+            Err(error) => eprintln!("Warning: {}", error),
+        }
+
+        match stopped.recv_timeout(watch::jittered(interval)) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Prints a new release event for `article` and runs the `--exec` hook, if any.
+fn emit(article: &Article, hook: Option<&String>) -> apple_releases::Result<()> {
+    let event = WatchEvent::NewRelease {
+        seen_at: chrono::Utc::now(),
+        article: article.clone(),
+    };
+    let json = serde_json::to_string(&event)
+        .map_err(|error| apple_releases::Error::Serialization(error.to_string()))?;
+
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", json)?;
+    stdout.flush()?;
+
+    if let Some(hook) = hook {
+        match watch::run_hook(hook, article) {
+            Ok(status) if !status.success() => {
+                eprintln!("Warning: {} exited with {}", hook, status)
             }
-        });
+            Ok(_) => {}
+            Err(error) => eprintln!("Warning: {}: {}", hook, error),
+        }
+    }
+    Ok(())
+}
+
+/// Reads articles from `--input`, or fetches them from `--source`, printing any warnings.
+fn fetch_articles(
+    args: &ArgMatches,
+    selectors: &Selectors,
+) -> apple_releases::Result<Vec<Article>> {
+    let source: Source = args.get_one::<String>("source").unwrap().parse()?;
+    let base_url = args.get_one::<String>("base-url").unwrap();
+    let feed_url = args.get_one::<String>("feed-url").unwrap();
+
+    let fetcher: Box<dyn Fetcher> = match args.get_one::<String>("input") {
+        Some(input) => Box::new(FileFetcher::new(input)),
+        None => http_fetcher(args)?,
+    };
+    let result = apple_releases::fetch_articles_from_source(
+        fetcher.as_ref(),
+        source,
+        base_url,
+        feed_url,
+        selectors,
+    )?;
+
+    for warning in &result.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(result.articles)
+}
+
+/// Builds the article filter from `--all`, `--product`, `--exclude-product`, `--since` and
+/// `--until`.
+fn filter(args: &ArgMatches) -> Filter {
+    let mut filter = Filter::new().all(args.get_flag("all"));
+    for product in args.get_many::<Product>("product").into_iter().flatten() {
+        filter = filter.product(product.clone());
+    }
+    for product in args
+        .get_many::<Product>("exclude-product")
+        .into_iter()
+        .flatten()
+    {
+        filter = filter.exclude_product(product.clone());
+    }
+    if let Some(since) = args.get_one::<NaiveDate>("since") {
+        filter = filter.since(*since);
+    }
+    if let Some(until) = args.get_one::<NaiveDate>("until") {
+        filter = filter.until(*until);
+    }
+    filter
 }
@@ -0,0 +1,449 @@
+//!
+//! output.rs
+//!
+
+use crate::article::Article;
+use crate::error::{Error, Result};
+use crate::software_release::SoftwareRelease;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::str::FromStr;
+
+/// Format used to write a list of articles.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// One human-readable line per article, using `Article`'s `Display`.
+    #[default]
+    Text,
+
+    /// A pretty-printed JSON array.
+    Json,
+
+    /// One compact JSON object per line.
+    Ndjson,
+
+    /// Comma-separated values with a header row. Nested fields are flattened.
+    Csv,
+
+    /// A YAML sequence.
+    Yaml,
+
+    /// A Markdown table. Nested fields are flattened.
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Names accepted by `FromStr`, in the order they are listed in help text.
+    pub const NAMES: [&'static str; 6] = ["text", "json", "ndjson", "csv", "yaml", "markdown"];
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(Error::Argument(format!("unknown output format '{}'", name))),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Markdown => "markdown",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// A single article flattened into columns for tabular formats.
+///
+/// The columns are `date`, `title`, `product`, `version`, `major`, `minor`, `patch`,
+/// `prerelease`, `build`, `stage`, `release_notes_url`, `permalink`, `id`, `marketing_name`,
+/// `title_html`, `links` (the URLs of the article's links, separated by spaces) and `body` (its
+/// plain text). They are also the placeholders of [`hook_command`](crate::watch::hook_command).
+#[derive(Serialize)]
+pub(crate) struct ArticleRecord {
+    date: String,
+    title: String,
+    product: Option<String>,
+    version: Option<String>,
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    prerelease: Option<String>,
+    build: Option<String>,
+    stage: Option<String>,
+    release_notes_url: Option<String>,
+    permalink: Option<String>,
+    id: Option<String>,
+    marketing_name: Option<String>,
+    title_html: Option<String>,
+    links: String,
+    body: Option<String>,
+}
+
+impl ArticleRecord {
+    /// Column names, matching the field order.
+    pub(crate) const HEADERS: [&'static str; 17] = [
+        "date",
+        "title",
+        "product",
+        "version",
+        "major",
+        "minor",
+        "patch",
+        "prerelease",
+        "build",
+        "stage",
+        "release_notes_url",
+        "permalink",
+        "id",
+        "marketing_name",
+        "title_html",
+        "links",
+        "body",
+    ];
+
+    pub(crate) fn new(article: &Article) -> Self {
+        let release = article.software_release.as_ref();
+        let version = release.map(SoftwareRelease::semver);
+        let version = version.as_ref();
+
+        Self {
+            date: article.date.format("%Y-%m-%d").to_string(),
+            title: article.title.clone(),
+            product: release.map(|release| release.product.to_string()),
+            version: version.map(|version| version.to_string()),
+            major: version.map(|version| version.major),
+            minor: version.map(|version| version.minor),
+            patch: version.map(|version| version.patch),
+            prerelease: release.and_then(SoftwareRelease::prerelease),
+            build: version
+                .filter(|version| !version.build.is_empty())
+                .map(|version| version.build.to_string()),
+            stage: release.map(|release| release.stage.to_string()),
+            release_notes_url: article
+                .release_notes_url
+                .as_ref()
+                .map(|url| url.to_string()),
+            permalink: article.permalink.as_ref().map(|url| url.to_string()),
+            id: article.id(),
+            marketing_name: release.and_then(|release| release.marketing_name.clone()),
+            title_html: article.title_html.clone(),
+            links: article
+                .links
+                .iter()
+                .map(|link| link.url.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            body: article.body.as_ref().map(|body| body.text.clone()),
+        }
+    }
+
+    /// Cell values, matching `HEADERS`. Missing values are empty strings.
+    pub(crate) fn cells(&self) -> [String; 17] {
+        fn cell<T: ToString>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        }
+
+        [
+            self.date.clone(),
+            self.title.clone(),
+            cell(&self.product),
+            cell(&self.version),
+            cell(&self.major),
+            cell(&self.minor),
+            cell(&self.patch),
+            cell(&self.prerelease),
+            cell(&self.build),
+            cell(&self.stage),
+            cell(&self.release_notes_url),
+            cell(&self.permalink),
+            cell(&self.id),
+            cell(&self.marketing_name),
+            cell(&self.title_html),
+            self.links.clone(),
+            cell(&self.body),
+        ]
+    }
+}
+
+/// Writes articles to `writer` in the given format.
+///
+/// # Arguments
+///
+/// - `writer` - Destination for the output.
+/// - `format` - The output format.
+/// - `articles` - The articles to write, in order.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::output::{OutputFormat, write_articles};
+///
+/// let html = r#"
+/// <section class="article-content-container">
+///     <a class="article-title" href="/download/"><h2>iOS 16.1.2 (20B110)</h2></a>
+///     <p class="article-date">November 30, 2022</p>
+/// </section>
+/// "#;
+/// let result = apple_releases::parse::parse_articles(html.to_string());
+/// let articles: Vec<_> = result.articles.iter().collect();
+///
+/// let mut buffer = Vec::new();
+/// write_articles(&mut buffer, OutputFormat::Ndjson, &articles).unwrap();
+/// assert!(String::from_utf8(buffer).unwrap().starts_with(r#"{"title":"iOS 16.1.2 (20B110)""#));
+/// ```
+pub fn write_articles<W: Write>(
+    mut writer: W,
+    format: OutputFormat,
+    articles: &[&Article],
+) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            for article in articles {
+                writeln!(writer, "{}", article)?;
+
+                // Announcements have no version to show, so describe them
+                if article.software_release.is_none()
+                    && let Some(body) = &article.body
+                {
+                    for line in body.text.lines() {
+                        if line.is_empty() {
+                            writeln!(writer)?;
+                        } else {
+                            writeln!(writer, "    {}", line)?;
+                        }
+                    }
+                }
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, articles)
+                .map_err(|error| Error::Serialization(error.to_string()))?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Ndjson => {
+            for article in articles {
+                serde_json::to_writer(&mut writer, article)
+                    .map_err(|error| Error::Serialization(error.to_string()))?;
+                writeln!(writer)?;
+            }
+        }
+        OutputFormat::Csv => {
+            // The header is written even when there are no articles
+            let mut csv_writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(writer);
+            csv_writer
+                .write_record(ArticleRecord::HEADERS)
+                .map_err(|error| Error::Serialization(error.to_string()))?;
+            for article in articles {
+                csv_writer
+                    .serialize(ArticleRecord::new(article))
+                    .map_err(|error| Error::Serialization(error.to_string()))?;
+            }
+            csv_writer.flush()?;
+        }
+        OutputFormat::Yaml => {
+            serde_yaml::to_writer(&mut writer, articles)
+                .map_err(|error| Error::Serialization(error.to_string()))?;
+        }
+        OutputFormat::Markdown => {
+            writeln!(writer, "| {} |", ArticleRecord::HEADERS.join(" | "))?;
+            writeln!(writer, "|{}", " --- |".repeat(ArticleRecord::HEADERS.len()))?;
+            for article in articles {
+                let cells = ArticleRecord::new(article)
+                    .cells()
+                    .map(|cell| cell.replace('|', "\\|").replace('\n', "<br>"));
+                writeln!(writer, "| {} |", cells.join(" | "))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn test_articles() -> Vec<Article> {
+    use crate::body::Body;
+    use crate::link::Link;
+    use chrono::NaiveDate;
+    use url::Url;
+
+    let date = NaiveDate::from_ymd_opt(2022, 11, 15).unwrap();
+    vec![
+        Article {
+            title: "iOS 16.2 beta 3 (20C5049e)".to_string(),
+            software_release: SoftwareRelease::software_release(
+                "iOS 16.2 beta 3 (20C5049e)".to_string(),
+            ),
+            date,
+            release_notes_url: Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").ok(),
+            permalink: Url::parse("https://developer.apple.com/news/releases/?id=11152022a").ok(),
+            links: vec![Link::new(
+                "View release notes",
+                Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").unwrap(),
+            )],
+            title_html: None,
+            body: Some(Body {
+                text: "View release notes".to_string(),
+                markdown: "[View release notes](https://developer.apple.com/go/?id=ios-16.2-rn)"
+                    .to_string(),
+            }),
+        },
+        Article {
+            title: "Upcoming requirements for app distribution".to_string(),
+            software_release: None,
+            date,
+            release_notes_url: None,
+            permalink: None,
+            links: Vec::new(),
+            title_html: None,
+            body: Some(Body {
+                text: "Apps must be built with Xcode 14.1.\n\nLearn more".to_string(),
+                markdown: "Apps must be built with Xcode 14.1.\n\n[Learn more](https://developer.apple.com/)"
+                    .to_string(),
+            }),
+        },
+    ]
+}
+
+#[cfg(test)]
+fn write_to_string(format: OutputFormat) -> String {
+    let articles = test_articles();
+    let articles: Vec<&Article> = articles.iter().collect();
+    let mut buffer = Vec::new();
+    write_articles(&mut buffer, format, &articles).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn test_output_format_from_str() {
+    for name in OutputFormat::NAMES {
+        let format: OutputFormat = name.parse().unwrap();
+        assert_eq!(format.to_string(), name);
+    }
+    assert_eq!("JSON".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+    assert_eq!(
+        "md".parse::<OutputFormat>().unwrap(),
+        OutputFormat::Markdown
+    );
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn test_write_text() {
+    assert_eq!(
+        write_to_string(OutputFormat::Text),
+        "2022-11-15 - iOS 16.2 beta 3 (20C5049e) - https://developer.apple.com/go/?id=ios-16.2-rn\n\
+         2022-11-15 - Upcoming requirements for app distribution\n    \
+         Apps must be built with Xcode 14.1.\n\n    Learn more\n"
+    );
+}
+
+#[test]
+fn test_write_json() {
+    let output = write_to_string(OutputFormat::Json);
+    let decoded: Vec<Article> = serde_json::from_str(&output).unwrap();
+    assert_eq!(decoded, test_articles());
+}
+
+#[test]
+fn test_write_ndjson() {
+    let output = write_to_string(OutputFormat::Ndjson);
+    let decoded: Vec<Article> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(decoded, test_articles());
+}
+
+#[test]
+fn test_write_yaml() {
+    let output = write_to_string(OutputFormat::Yaml);
+    let decoded: Vec<Article> = serde_yaml::from_str(&output).unwrap();
+    assert_eq!(decoded, test_articles());
+}
+
+#[test]
+fn test_write_csv() {
+    let output = write_to_string(OutputFormat::Csv);
+    let mut lines = output.lines();
+    assert_eq!(lines.next().unwrap(), ArticleRecord::HEADERS.join(","));
+    assert_eq!(
+        lines.next().unwrap(),
+        "2022-11-15,iOS 16.2 beta 3 (20C5049e),iOS,16.2.0-beta-3+20C5049e,16,2,0,beta-3,20C5049e,beta 3,\
+         https://developer.apple.com/go/?id=ios-16.2-rn,https://developer.apple.com/news/releases/?id=11152022a,11152022a,,,\
+         https://developer.apple.com/go/?id=ios-16.2-rn,View release notes"
+    );
+    assert_eq!(
+        lines.next().unwrap(),
+        "2022-11-15,Upcoming requirements for app distribution,,,,,,,,,,,,,,,\"Apps must be built with Xcode 14.1."
+    );
+    assert_eq!(lines.next().unwrap(), "");
+    assert_eq!(lines.next().unwrap(), "Learn more\"");
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn test_write_markdown() {
+    let output = write_to_string(OutputFormat::Markdown);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("| date | title | product |"));
+    assert_eq!(
+        lines[1].matches("---").count(),
+        ArticleRecord::HEADERS.len()
+    );
+    assert!(lines[2].starts_with("| 2022-11-15 | iOS 16.2 beta 3 (20C5049e) | iOS |"));
+    assert_eq!(
+        lines[3],
+        "| 2022-11-15 | Upcoming requirements for app distribution |  |  |  |  |  |  |  |  |  |  |  \
+         |  |  |  | Apps must be built with Xcode 14.1.<br><br>Learn more |"
+    );
+}
+
+#[test]
+fn test_write_tabular_without_articles() {
+    let write = |format| {
+        let mut buffer = Vec::new();
+        write_articles(&mut buffer, format, &[]).unwrap();
+        String::from_utf8(buffer).unwrap()
+    };
+
+    assert_eq!(
+        write(OutputFormat::Csv),
+        format!("{}\n", ArticleRecord::HEADERS.join(","))
+    );
+
+    let output = write(OutputFormat::Markdown);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[0],
+        format!("| {} |", ArticleRecord::HEADERS.join(" | "))
+    );
+    assert_eq!(
+        lines[1].matches("---").count(),
+        ArticleRecord::HEADERS.len()
+    );
+}
@@ -3,47 +3,179 @@
 //!
 
 use crate::article::Article;
+use crate::body::{self, Body};
+use crate::error::{Error, Result};
+use crate::link::{self, Link};
+use crate::selectors::{SelectorList, Selectors};
 use crate::software_release::SoftwareRelease;
-use crate::{GenericResult, SELECTORS};
+use crate::title::normalize_title;
+use crate::{RELEASES_PAGE, SELECTORS};
 use chrono::NaiveDate;
-use scraper::{ElementRef, Html, Selector};
+use scraper::{ElementRef, Html};
+use std::fmt::{Display, Formatter};
+use url::Url;
+
+/// Articles found in a page, along with diagnostics for those which could not be parsed.
+#[derive(Debug, Default)]
+pub struct ParseResult {
+    /// Successfully parsed articles, in page order.
+    pub articles: Vec<Article>,
+
+    /// Problems encountered while parsing individual articles.
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// A problem parsing a single article.
+///
+/// Articles missing a title or date are dropped. Articles with a malformed version or release notes
+/// link are kept without that field.
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// The raw article title, if one was found.
+    pub title: Option<String>,
+
+    /// The HTML of the article container which failed to parse.
+    pub html: String,
+
+    /// What went wrong.
+    pub error: Error,
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.title {
+            Some(title) => write!(formatter, "{}: {}", title, self.error),
+            None => write!(formatter, "{}", self.error),
+        }
+    }
+}
 
 /// Finds articles in the HTML.
 ///
+/// Malformed articles do not stop parsing; they are reported in [`ParseResult::warnings`].
+///
+/// # Arguments
+///
+/// - `content` - The HTML to parse.
+///
+/// # Returns
+///
+/// The parsed articles and any per-article warnings.
+///
+/// # Example
+///
+/// ```
+/// let html = r#"
+/// <section class="article-content-container">
+///     <a class="article-title" href="/download/"><h2>iOS 16.1.2 (20B110)</h2></a>
+///     <p class="article-date">November 30, 2022</p>
+/// </section>
+/// "#;
+///
+/// let result = apple_releases::parse::parse_articles(html.to_string());
+/// assert_eq!(result.articles.len(), 1);
+/// assert_eq!(result.articles[0].title, "iOS 16.1.2 (20B110)");
+/// assert!(result.warnings.is_empty());
+/// ```
+pub fn parse_articles(content: String) -> ParseResult {
+    parse_articles_with(content, &SELECTORS, &RELEASES_PAGE)
+}
+
+/// Finds articles in the HTML using custom selectors.
+///
 /// # Arguments
 ///
 /// - `content` - The HTML to parse.
+/// - `selectors` - Where to find each part of an article, e.g. from
+///   [`Selectors::with_config`](crate::selectors::Selectors::with_config).
+/// - `page_url` - URL of the page, which relative links are resolved against.
 ///
 /// # Returns
 ///
-/// A list of articles.
-pub fn parse_articles(content: String) -> GenericResult<Vec<Article>> {
+/// The parsed articles and any per-article warnings.
+pub fn parse_articles_with(content: String, selectors: &Selectors, page_url: &Url) -> ParseResult {
     let document = Html::parse_document(&content);
-    let mut articles: Vec<Article> = Vec::new();
-
-    for container in document.select(&SELECTORS.article) {
-        let title = parse_article_title(&container, &SELECTORS.title).expect("title");
-        let date = parse_article_date(&container, &SELECTORS.date).expect("date");
-        let notes = parse_release_notes_link(&container, &SELECTORS.release_notes_short_url);
-        let notes_url = crate::url::build_notes_url(notes)
-            // Ignore Transporter app store links
-            .filter(|url| url.as_str().contains("developer.apple.com"));
-
-        // TODO: Log debug
-        // let url = notes_url.as_ref().map_or(None, |url| Some(url.to_string()));
-        // println!("{} - {}, <{}>", date, title, url.unwrap_or_default());
-
-        let article = Article {
-            title: title.clone(),
-            software_release: SoftwareRelease::software_release(title),
-            date,
-            release_notes_url: notes_url,
+    let mut result = ParseResult::default();
+
+    for container in selectors.article.select(&document.root_element()) {
+        let (title, title_html) = match parse_article_title(&container, &selectors.title) {
+            Ok(title) => title,
+            Err(error) => {
+                result.warnings.push(ParseWarning {
+                    title: None,
+                    html: container.html(),
+                    error,
+                });
+                continue;
+            }
         };
 
-        articles.push(article);
+        let date = match parse_article_date(&container, &selectors.date) {
+            Ok(date) => date,
+            Err(error) => {
+                result.warnings.push(ParseWarning {
+                    title: Some(title),
+                    html: container.html(),
+                    error,
+                });
+                continue;
+            }
+        };
+
+        let body = parse_body(&container, &selectors.body, page_url);
+        let links = build_links(
+            page_url,
+            parse_links(&container, &selectors.links),
+            |error| {
+                result.warnings.push(ParseWarning {
+                    title: Some(title.clone()),
+                    html: container.html(),
+                    error,
+                })
+            },
+        );
+
+        let href = parse_permalink(&container, &selectors.permalink);
+        let permalink = match href
+            .map(|href| crate::url::resolve(page_url, &href))
+            .transpose()
+        {
+            Ok(url) => url,
+            Err(error) => {
+                result.warnings.push(ParseWarning {
+                    title: Some(title.clone()),
+                    html: container.html(),
+                    error,
+                });
+                None
+            }
+        };
+
+        let software_release = match SoftwareRelease::parse(&title) {
+            Ok(release) => release,
+            Err(error) => {
+                result.warnings.push(ParseWarning {
+                    title: Some(title.clone()),
+                    html: container.html(),
+                    error,
+                });
+                None
+            }
+        };
+
+        result.articles.push(Article {
+            title_html: Some(title_html).filter(|html| *html != title),
+            title,
+            software_release,
+            date,
+            release_notes_url: link::release_notes_url(&links, page_url),
+            permalink,
+            links,
+            body,
+        });
     }
 
-    Ok(articles)
+    result
 }
 
 /// Parses the article title.
@@ -51,13 +183,27 @@ pub fn parse_articles(content: String) -> GenericResult<Vec<Article>> {
 /// # Arguments
 ///
 /// - `element` - The HTML ElementRef to parse.
-/// - `selector` - The selector to use.
-pub fn parse_article_title(element: &ElementRef, selector: &Selector) -> GenericResult<String> {
-    Ok(element
-        .select(selector)
+/// - `selector` - The selectors to try, in order.
+///
+/// # Returns
+///
+/// The title as text, with entities decoded, markup removed and whitespace collapsed, and the
+/// title's raw HTML.
+pub(crate) fn parse_article_title(
+    element: &ElementRef,
+    selector: &SelectorList,
+) -> Result<(String, String)> {
+    let title = selector
+        .select(element)
+        .into_iter()
         .next()
-        .ok_or("No title found")?
-        .inner_html())
+        .ok_or_else(|| Error::Html("No title found".to_string()))?;
+
+    let text = normalize_title(&title.text().collect::<String>());
+    if text.is_empty() {
+        return Err(Error::Html("Empty title".to_string()));
+    }
+    Ok((text, title.inner_html()))
 }
 
 /// Parses the article date.
@@ -65,39 +211,128 @@ pub fn parse_article_title(element: &ElementRef, selector: &Selector) -> Generic
 /// # Arguments
 ///
 /// - `element` - The HTML ElementRef to parse.
-/// - `selector` - The selector to use.
-pub fn parse_article_date(element: &ElementRef, selector: &Selector) -> GenericResult<NaiveDate> {
-    let date_string = element
-        .select(selector)
+/// - `selector` - The selectors to try, in order.
+pub(crate) fn parse_article_date(
+    element: &ElementRef,
+    selector: &SelectorList,
+) -> Result<NaiveDate> {
+    let date_string = selector
+        .select(element)
+        .into_iter()
         .next()
-        .ok_or("No date found")?
+        .ok_or_else(|| Error::Html("No date found".to_string()))?
         .inner_html();
 
-    Ok(NaiveDate::parse_from_str(
-        date_string.as_str(),
-        "%B %d, %Y",
-    )?)
+    NaiveDate::parse_from_str(date_string.trim(), "%B %d, %Y").map_err(|source| Error::Date {
+        value: date_string.clone(),
+        source,
+    })
 }
 
-/// Parses the release notes link.
+/// Parses the article body.
 ///
 /// # Arguments
 ///
 /// - `element` - The HTML ElementRef to parse.
-/// - `selector` - The selector to use.
-pub fn parse_release_notes_link(element: &ElementRef, selector: &Selector) -> Option<String> {
-    element
-        .select(selector)
+/// - `selector` - The selectors to try, in order.
+/// - `page_url` - URL of the page, which relative links are resolved against.
+pub(crate) fn parse_body(
+    element: &ElementRef,
+    selector: &SelectorList,
+    page_url: &Url,
+) -> Option<Body> {
+    selector
+        .select(element)
+        .into_iter()
         .next()
-        .ok_or("No release notes link found")
-        .ok()?
-        .value()
-        .attr("href")
+        .and_then(|body| body::from_html(&body, page_url))
+}
+
+/// Parses the text and href of each link in the article body.
+///
+/// # Arguments
+///
+/// - `element` - The HTML ElementRef to parse.
+/// - `selector` - The selectors to try, in order.
+pub(crate) fn parse_links(element: &ElementRef, selector: &SelectorList) -> Vec<(String, String)> {
+    selector
+        .select(element)
+        .into_iter()
+        .filter_map(|link| {
+            let href = link.value().attr("href")?.trim();
+            Some((link.text().collect(), href.to_string()))
+        })
+        .filter(|(_, href)| !href.is_empty())
+        .collect()
+}
+
+/// Resolves and classifies links found by [`parse_links`].
+///
+/// # Arguments
+///
+/// - `base` - URL of the page, which relative links are resolved against.
+/// - `links` - The text and href of each link.
+/// - `warn` - Called with the error for each href which is not a valid URL.
+pub(crate) fn build_links(
+    base: &Url,
+    links: Vec<(String, String)>,
+    mut warn: impl FnMut(Error),
+) -> Vec<Link> {
+    links
+        .into_iter()
+        .filter_map(|(text, href)| match crate::url::resolve(base, &href) {
+            Ok(url) => Some(Link::new(&text, url)),
+            Err(error) => {
+                warn(error);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parses the article permalink from its share buttons.
+///
+/// # Arguments
+///
+/// - `element` - The HTML ElementRef to parse.
+/// - `selector` - The selectors to try, in order.
+pub(crate) fn parse_permalink(element: &ElementRef, selector: &SelectorList) -> Option<String> {
+    selector
+        .select(element)
+        .into_iter()
+        .find_map(|button| button.value().attr("data-href"))
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Parses the link to the next page of articles.
+///
+/// # Arguments
+///
+/// - `element` - The HTML ElementRef to parse.
+/// - `selector` - The selectors to try, in order.
+pub(crate) fn parse_next_page_link(
+    element: &ElementRef,
+    selector: &SelectorList,
+) -> Option<String> {
+    selector
+        .select(element)
+        .into_iter()
+        .find_map(|link| link.value().attr("href"))
         .map(|url| url.to_string())
 }
 
 /* ---------------------------------------------------------------------------------------------- */
 
+#[cfg(test)]
+use crate::product::Product;
+#[cfg(test)]
+use crate::release_stage::ReleaseStage;
+#[cfg(test)]
+use scraper::Selector;
+#[cfg(test)]
+use semver::{BuildMetadata, Version};
+
 #[test]
 fn test_parse() {
     let html = r###"
@@ -131,8 +366,47 @@ fn test_parse() {
 </section>
     "###.to_string();
 
-    let articles = parse_articles(html).expect("Err collecting articles");
-    assert_eq!(articles.len(), 1);
+    let result = parse_articles(html);
+    assert_eq!(result.articles.len(), 1);
+    assert!(result.warnings.is_empty());
+
+    let article = &result.articles[0];
+    assert_eq!(
+        article.permalink.as_ref().unwrap().as_str(),
+        "https://developer.apple.com/news/releases/?id=08082022a"
+    );
+    assert_eq!(article.id().as_deref(), Some("08082022a"));
+    assert_eq!(
+        article.body.as_ref().unwrap().text,
+        "View downloads\n\nView release notes"
+    );
+}
+
+#[test]
+fn test_parse_body() {
+    let html = r###"
+<section class="article-content-container">
+    <a class="article-title" href="/app-store-connect/"><h2>Sales reports by region</h2></a>
+    <p class="article-date">December 13, 2022</p>
+    <span class="article-text">
+        <p>You can now filter <b>Sales and Trends</b> by region in
+        <a href="https://appstoreconnect.apple.com/">App Store Connect</a>.</p>
+    </span>
+</section>
+    "###
+    .to_string();
+
+    let result = parse_articles(html);
+    let body = result.articles[0].body.as_ref().unwrap();
+    assert_eq!(
+        body.text,
+        "You can now filter Sales and Trends by region in App Store Connect."
+    );
+    assert_eq!(
+        body.markdown,
+        "You can now filter **Sales and Trends** by region in \
+         [App Store Connect](https://appstoreconnect.apple.com/)."
+    );
 }
 
 #[test]
@@ -149,11 +423,55 @@ fn test_parse_title() {
     let element = fragment.select(&selector).next().unwrap();
     println!("{}", element.inner_html());
 
-    let title = parse_article_title(&fragment.root_element(), &SELECTORS.title).unwrap();
+    let (title, html) = parse_article_title(&fragment.root_element(), &SELECTORS.title).unwrap();
 
     assert_eq!(title, "Xcode 14 beta 5 (14A5294e)");
+    assert_eq!(html, title);
 }
 
+#[test]
+fn test_parse_title_markup() {
+    let html = r###"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>iOS&nbsp;17.2 <span class="badge">beta</span>
+        2 <sup>(21C5046c)</sup></h2></a>
+    <p class="article-date">November 14, 2023</p>
+</section>
+<section class="article-content-container">
+    <a class="article-title" href="/app-store-connect/"><h2>App Store Connect &amp; TestFlight</h2></a>
+    <p class="article-date">November 14, 2023</p>
+</section>
+<section class="article-content-container">
+    <a class="article-title" href="/"><h2> <span></span> </h2></a>
+    <p class="article-date">November 14, 2023</p>
+</section>
+    "###
+    .to_string();
+
+    let result = parse_articles(html);
+    assert_eq!(result.articles.len(), 2);
+
+    let article = &result.articles[0];
+    assert_eq!(article.title, "iOS 17.2 beta 2 (21C5046c)");
+    assert!(article.title_html.as_ref().unwrap().contains("<sup>"));
+    let release = article.software_release.as_ref().unwrap();
+    assert_eq!(release.product, Product::iOS);
+    assert_eq!(release.stage, ReleaseStage::DeveloperBeta(2));
+    assert_eq!(release.version.build.as_str(), "21C5046c");
+
+    let article = &result.articles[1];
+    assert_eq!(article.title, "App Store Connect & TestFlight");
+    assert_eq!(
+        article.title_html.as_deref(),
+        Some("App Store Connect &amp; TestFlight")
+    );
+
+    // The empty title
+    assert!(matches!(
+        result.warnings.last().unwrap().error,
+        Error::Html(_)
+    ));
+}
 
 #[test]
 fn test_software_release() {
@@ -163,7 +481,6 @@ fn test_software_release() {
     assert_eq!(release.product, Product::Xcode);
 
     let mut expected_version = Version::new(14, 0, 0);
-    expected_version.pre = Prerelease::new("beta-5").unwrap();
     expected_version.build = BuildMetadata::new("14A5294e").unwrap();
     assert_eq!(release.version, expected_version);
 }
@@ -189,27 +506,184 @@ fn test_parse_date() {
 }
 
 #[test]
-fn test_parse_release_notes_link() {
+fn test_parse_links() {
+    use crate::link::LinkKind;
+
     let html = r###"
         <span class="article-text">
             <ul class="links-stacked">
                 <li><a href="/download/applications" class="more">View downloads</a></li>
                 <li><a href="/go/?id=xcode-16_1-sdk-rn" class="more">View release notes</a></li>
+                <li><a href="/xcode/whats-new/" class="more">What’s new in Xcode</a></li>
+                <li><a href="http://[::1" class="more">Broken</a></li>
             </ul>
         </span>
     "###
     .to_string();
 
     let fragment = Html::parse_fragment(&html);
+    let links = parse_links(&fragment.root_element(), &SELECTORS.links);
+    assert_eq!(links.len(), 4);
+    assert_eq!(
+        links[1],
+        (
+            "View release notes".to_string(),
+            "/go/?id=xcode-16_1-sdk-rn".to_string()
+        )
+    );
+
+    let mut errors = Vec::new();
+    let links = build_links(&RELEASES_PAGE, links, |error| errors.push(error));
+    let kinds: Vec<LinkKind> = links.iter().map(|link| link.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            LinkKind::Downloads,
+            LinkKind::ReleaseNotes,
+            LinkKind::Documentation
+        ]
+    );
+    assert_eq!(
+        links[1].url.as_str(),
+        "https://developer.apple.com/go/?id=xcode-16_1-sdk-rn"
+    );
+    assert!(matches!(errors[..], [Error::Url { .. }]));
+}
 
-    // test parsing using local selector
-    let selector = Selector::parse(r#"span.article-text ul.links-stacked li:nth-child(2) a.more"#).unwrap();
-    let element = fragment.select(&selector).next().unwrap().value();
-    println!("{}", element.attr("href").unwrap());
+#[test]
+fn test_parse_links_against_page_url() {
+    let html = r###"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>iOS 16.2 (20C65)</h2></a>
+    <p class="article-date">December 13, 2022</p>
+    <span class="article-text">
+        <p>See <a href="/go/?id=ios-16.2-rn">the release notes</a>.</p>
+    </span>
+    <div class="social-share-container"><a data-href="?id=12132022a">Share</a></div>
+</section>
+    "###
+    .to_string();
 
-    let notes_url =
-        parse_release_notes_link(&fragment.root_element(), &SELECTORS.release_notes_short_url)
-            .unwrap();
+    let page_url = Url::parse("http://localhost:8080/news/releases/").unwrap();
+    let result = parse_articles_with(html, &SELECTORS, &page_url);
+    let article = &result.articles[0];
+    assert_eq!(
+        article.links[0].url.as_str(),
+        "http://localhost:8080/go/?id=ios-16.2-rn"
+    );
+    assert_eq!(
+        article.release_notes_url.as_ref().unwrap().as_str(),
+        "http://localhost:8080/go/?id=ios-16.2-rn"
+    );
+    assert_eq!(
+        article.body.as_ref().unwrap().markdown,
+        "See [the release notes](http://localhost:8080/go/?id=ios-16.2-rn)."
+    );
+    assert_eq!(
+        article.permalink.as_ref().unwrap().as_str(),
+        "http://localhost:8080/news/releases/?id=12132022a"
+    );
+}
+
+#[test]
+fn test_parse_release_notes_first() {
+    let html = r###"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>iOS 16.2 (20C65)</h2></a>
+    <p class="article-date">December 13, 2022</p>
+    <span class="article-text">
+        <ul class="links-stacked">
+            <li><a href="/go/?id=ios-16.2-rn" class="more">View release notes</a></li>
+        </ul>
+    </span>
+</section>
+    "###
+    .to_string();
+
+    let result = parse_articles(html);
+    let article = &result.articles[0];
+    assert_eq!(article.links.len(), 1);
+    assert_eq!(
+        article.release_notes_url.as_ref().unwrap().as_str(),
+        "https://developer.apple.com/go/?id=ios-16.2-rn"
+    );
+}
 
-    assert_eq!(notes_url, "/go/?id=xcode-16_1-sdk-rn");
+#[test]
+fn test_parse_malformed_articles() {
+    let html = r###"
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>iOS 16.1.2 (20B110)</h2></a>
+    <p class="article-date">November 30, 2022</p>
+</section>
+<section class="article-content-container">
+    <p class="article-date">November 30, 2022</p>
+</section>
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>Xcode 14.1 (14B47b)</h2></a>
+    <p class="article-date">Smarch 1, 2022</p>
+</section>
+<section class="article-content-container">
+    <a class="article-title" href="/download/"><h2>iOS 16.x</h2></a>
+    <p class="article-date">November 30, 2022</p>
+</section>
+    "###
+    .to_string();
+
+    let result = parse_articles(html);
+
+    let titles: Vec<&str> = result.articles.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(titles, vec!["iOS 16.1.2 (20B110)", "iOS 16.x"]);
+    assert!(result.articles[1].software_release.is_none());
+
+    assert_eq!(result.warnings.len(), 3);
+    assert!(matches!(result.warnings[0].error, Error::Html(_)));
+    assert_eq!(result.warnings[0].title, None);
+    assert!(result.warnings[0].html.contains("article-date"));
+    assert!(matches!(result.warnings[1].error, Error::Date { .. }));
+    assert_eq!(
+        result.warnings[1].title.as_deref(),
+        Some("Xcode 14.1 (14B47b)")
+    );
+    assert!(matches!(result.warnings[2].error, Error::Version { .. }));
+}
+
+#[test]
+fn test_parse_next_page_link() {
+    let html = r###"
+        <nav class="pagination">
+            <a href="/news/releases/?page=1" class="previous">Newer</a>
+            <a href="/news/releases/?page=3" class="next">Older</a>
+        </nav>
+    "###;
+    let fragment = Html::parse_fragment(html);
+    let link = parse_next_page_link(&fragment.root_element(), &SELECTORS.next_page);
+    assert_eq!(link.as_deref(), Some("/news/releases/?page=3"));
+
+    let fragment = Html::parse_fragment(r#"<a href="/news/">News</a>"#);
+    assert_eq!(
+        parse_next_page_link(&fragment.root_element(), &SELECTORS.next_page),
+        None
+    );
+}
+
+#[test]
+fn test_parse_permalink() {
+    let html = r###"
+        <section class="social-share-container">
+            <button class="icon icon-facebook social-icon" data-href="https://developer.apple.com/news/releases/?id=08082022a" data-share-type="facebook"></button>
+        </section>
+    "###;
+    let fragment = Html::parse_fragment(html);
+    let permalink = parse_permalink(&fragment.root_element(), &SELECTORS.permalink);
+    assert_eq!(
+        permalink.as_deref(),
+        Some("https://developer.apple.com/news/releases/?id=08082022a")
+    );
+
+    let fragment = Html::parse_fragment(r#"<section class="social-share-container"></section>"#);
+    assert_eq!(
+        parse_permalink(&fragment.root_element(), &SELECTORS.permalink),
+        None
+    );
 }
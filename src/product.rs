@@ -2,12 +2,38 @@
 //! product.rs
 //!
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::fmt::Display;
+use std::str::FromStr;
 
+/// An Apple product with versioned software releases.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::Product;
+///
+/// let product: Product = "Xcode".parse().unwrap();
+/// assert_eq!(product, Product::Xcode);
+/// assert_eq!(product.to_string(), "Xcode");
+///
+/// // Parsing is case-insensitive and accepts common aliases.
+/// assert_eq!("mac".parse::<Product>().unwrap(), Product::macOS);
+/// assert_eq!("asc".parse::<Product>().unwrap(), Product::AppStoreConnect);
+///
+/// // Unknown names are rejected by `FromStr` but kept by `from_name`.
+/// assert!("Final Cut Pro".parse::<Product>().is_err());
+/// assert_eq!(
+///     Product::from_name("Final Cut Pro"),
+///     Product::Other("Final Cut Pro".to_string())
+/// );
+/// ```
+///
+/// Serializes as its display name, e.g. `"iPadOS"` or `"App Store Connect"`.
 #[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq, enum_utils::FromStr)]
-pub(crate) enum Product {
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Product {
     iOS,
     iPadOS,
     macOS,
@@ -15,6 +41,65 @@ pub(crate) enum Product {
     visionOS,
     watchOS,
     Xcode,
+    AppleConfigurator,
+    AppStoreConnect,
+    CreateML,
+    RealityComposerPro,
+    Safari,
+    SFSymbols,
+    SwiftPlaygrounds,
+    TestFlight,
+    Transporter,
+    XcodeCloud,
+
+    /// A product not listed above, named as it appears in the article title.
+    Other(String),
+}
+
+impl Product {
+    /// Every product except `Other`.
+    pub(crate) const KNOWN: [Product; 17] = [
+        Product::iOS,
+        Product::iPadOS,
+        Product::macOS,
+        Product::tvOS,
+        Product::visionOS,
+        Product::watchOS,
+        Product::Xcode,
+        Product::AppleConfigurator,
+        Product::AppStoreConnect,
+        Product::CreateML,
+        Product::RealityComposerPro,
+        Product::Safari,
+        Product::SFSymbols,
+        Product::SwiftPlaygrounds,
+        Product::TestFlight,
+        Product::Transporter,
+        Product::XcodeCloud,
+    ];
+
+    /// Matches the display name of a known product, ignoring case. Unlike `FromStr`, aliases such
+    /// as `mac` or `watch` are not accepted, since they also start unrelated titles like
+    /// `Mac Pro` or `Watch Party`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The name as it appears in an article title.
+    pub(crate) fn from_display_name(name: &str) -> Option<Product> {
+        Product::KNOWN
+            .into_iter()
+            .find(|product| product.to_string().eq_ignore_ascii_case(name))
+    }
+
+    /// Parses a product name, falling back to `Other` for names which are not recognized.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The product name.
+    pub fn from_name(name: &str) -> Product {
+        name.parse()
+            .unwrap_or_else(|_| Product::Other(name.to_string()))
+    }
 }
 
 impl Display for Product {
@@ -27,10 +112,65 @@ impl Display for Product {
             Product::visionOS => write!(f, "visionOS"),
             Product::watchOS => write!(f, "watchOS"),
             Product::Xcode => write!(f, "Xcode"),
+            Product::AppleConfigurator => write!(f, "Apple Configurator"),
+            Product::AppStoreConnect => write!(f, "App Store Connect"),
+            Product::CreateML => write!(f, "Create ML"),
+            Product::RealityComposerPro => write!(f, "Reality Composer Pro"),
+            Product::Safari => write!(f, "Safari"),
+            Product::SFSymbols => write!(f, "SF Symbols"),
+            Product::SwiftPlaygrounds => write!(f, "Swift Playgrounds"),
+            Product::TestFlight => write!(f, "TestFlight"),
+            Product::Transporter => write!(f, "Transporter"),
+            Product::XcodeCloud => write!(f, "Xcode Cloud"),
+            Product::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl FromStr for Product {
+    type Err = ();
+
+    /// Parses a known product name, ignoring case. Accepts aliases such as `mac` or `watch`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "ios" | "iphoneos" => Ok(Product::iOS),
+            "ipados" => Ok(Product::iPadOS),
+            "macos" | "mac" | "osx" => Ok(Product::macOS),
+            "tvos" | "appletv" => Ok(Product::tvOS),
+            "visionos" | "vision" | "xros" => Ok(Product::visionOS),
+            "watchos" | "watch" => Ok(Product::watchOS),
+            "xcode" => Ok(Product::Xcode),
+            "apple configurator" | "appleconfigurator" | "configurator" => {
+                Ok(Product::AppleConfigurator)
+            }
+            "app store connect" | "appstoreconnect" | "asc" => Ok(Product::AppStoreConnect),
+            "create ml" | "createml" => Ok(Product::CreateML),
+            "reality composer pro" | "realitycomposerpro" => Ok(Product::RealityComposerPro),
+            "safari" => Ok(Product::Safari),
+            "sf symbols" | "sfsymbols" => Ok(Product::SFSymbols),
+            "swift playgrounds" | "swiftplaygrounds" | "playgrounds" => {
+                Ok(Product::SwiftPlaygrounds)
+            }
+            "testflight" => Ok(Product::TestFlight),
+            "transporter" => Ok(Product::Transporter),
+            "xcode cloud" | "xcodecloud" => Ok(Product::XcodeCloud),
+            _ => Err(()),
         }
     }
 }
 
+impl Serialize for Product {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Product {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Product::from_name(&String::deserialize(deserializer)?))
+    }
+}
+
 /* ---------------------------------------------------------------------------------------------- */
 
 #[test]
@@ -43,9 +183,77 @@ fn parse_product_existing_variants() {
     assert_eq!(Product::Xcode, "Xcode".parse().unwrap());
 }
 
+#[test]
+fn parse_product_case_insensitive_aliases() {
+    assert_eq!(Product::iOS, "IOS".parse().unwrap());
+    assert_eq!(Product::iPadOS, "ipados".parse().unwrap());
+    assert_eq!(Product::macOS, "mac".parse().unwrap());
+    assert_eq!(Product::macOS, "MacOS".parse().unwrap());
+    assert_eq!(Product::visionOS, "xros".parse().unwrap());
+    assert_eq!(Product::watchOS, "watch".parse().unwrap());
+    assert_eq!(Product::Xcode, "xcode".parse().unwrap());
+}
+
+#[test]
+fn parse_product_multi_word() {
+    assert_eq!(
+        Product::AppStoreConnect,
+        "App Store Connect".parse().unwrap()
+    );
+    assert_eq!(
+        Product::RealityComposerPro,
+        "reality composer pro".parse().unwrap()
+    );
+    assert_eq!(Product::SFSymbols, "SF Symbols".parse().unwrap());
+    assert_eq!(Product::XcodeCloud, "Xcode Cloud".parse().unwrap());
+    assert_eq!(Product::CreateML.to_string(), "Create ML");
+}
+
+#[test]
+fn parse_product_other() {
+    let product = Product::from_name("Final Cut Pro");
+    assert_eq!(product, Product::Other("Final Cut Pro".to_string()));
+    assert_eq!(product.to_string(), "Final Cut Pro");
+    assert_eq!(Product::from_name("testflight"), Product::TestFlight);
+}
+
 #[test]
 fn parse_product_nonexistent_variant() {
     let result = "blah".parse::<Product>();
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), ());
 }
+
+#[test]
+fn serde_product_uses_display_name() {
+    assert_eq!(
+        serde_json::to_string(&Product::iPadOS).unwrap(),
+        r#""iPadOS""#
+    );
+    let product: Product = serde_json::from_str(r#""visionOS""#).unwrap();
+    assert_eq!(product, Product::visionOS);
+
+    let product = Product::Other("Final Cut Pro".to_string());
+    let json = serde_json::to_string(&product).unwrap();
+    assert_eq!(json, r#""Final Cut Pro""#);
+    assert_eq!(serde_json::from_str::<Product>(&json).unwrap(), product);
+}
+
+#[test]
+fn product_from_display_name() {
+    assert_eq!(Product::from_display_name("macOS"), Some(Product::macOS));
+    assert_eq!(
+        Product::from_display_name("app store connect"),
+        Some(Product::AppStoreConnect)
+    );
+    assert_eq!(Product::from_display_name("mac"), None);
+    assert_eq!(Product::from_display_name("Watch"), None);
+    assert_eq!(Product::from_display_name("Final Cut Pro"), None);
+
+    for product in Product::KNOWN {
+        assert_eq!(
+            Product::from_display_name(&product.to_string()),
+            Some(product)
+        );
+    }
+}
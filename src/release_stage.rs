@@ -0,0 +1,272 @@
+//!
+//! release_stage.rs
+//!
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Stage of a software release in Apple's beta cycle.
+///
+/// Stages are ordered the way Apple ships them: betas, then release candidates, then the release.
+/// Stages which could not be recognized, such as a Rapid Security Response `(a)` or an `Update`,
+/// ship after the release of the same version, so they sort after everything else.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::ReleaseStage;
+///
+/// let beta_2: ReleaseStage = "beta 2".parse().unwrap();
+/// let beta_10: ReleaseStage = "beta 10".parse().unwrap();
+/// let rc: ReleaseStage = "RC".parse().unwrap();
+///
+/// assert!(beta_2 < beta_10);
+/// assert!(beta_10 < rc);
+/// assert!(rc < ReleaseStage::Release);
+/// ```
+///
+/// Serializes as its display string, e.g. `"beta 3"`, `"RC 2"` or `"Release"`. Seed numbers are
+/// normalized: a lone `beta` is the first seed and displays as `beta 1`, while the first release
+/// candidate displays as `RC` however it was written.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum ReleaseStage {
+    /// A developer beta seed, e.g. `beta 3`. A lone `beta` is number 1.
+    DeveloperBeta(u32),
+
+    /// A public beta seed, e.g. `Public Beta 2`. A lone `Public Beta` is number 1.
+    PublicBeta(u32),
+
+    /// A release candidate, e.g. `RC 2`. A lone `RC` is number 1.
+    ReleaseCandidate(u32),
+
+    /// A final release.
+    #[default]
+    Release,
+
+    /// Words after the version which are not a recognized stage, e.g. `Update`.
+    Unknown(String),
+}
+
+impl ReleaseStage {
+    /// Determines the stage from the words between a version and its build number.
+    ///
+    /// # Arguments
+    ///
+    /// - `words` - The words to parse, e.g. `["beta", "3"]`. Empty means a release.
+    pub fn from_words(words: &[&str]) -> ReleaseStage {
+        let lowercase: Vec<String> = words.iter().map(|word| word.to_ascii_lowercase()).collect();
+        let lowercase: Vec<&str> = lowercase.iter().map(String::as_str).collect();
+
+        // Number following the stage name, defaulting to the first seed.
+        let number = |rest: &[&str]| -> Option<u32> {
+            match rest {
+                [] => Some(1),
+                [number] => number.parse().ok(),
+                _ => None,
+            }
+        };
+
+        let stage = match lowercase.as_slice() {
+            [] => Some(ReleaseStage::Release),
+            ["beta", rest @ ..] | ["developer", "beta", rest @ ..] => {
+                number(rest).map(ReleaseStage::DeveloperBeta)
+            }
+            ["public", "beta", rest @ ..] => number(rest).map(ReleaseStage::PublicBeta),
+            ["rc", rest @ ..] | ["release", "candidate", rest @ ..] => {
+                number(rest).map(ReleaseStage::ReleaseCandidate)
+            }
+            _ => None,
+        };
+
+        stage.unwrap_or_else(|| ReleaseStage::Unknown(words.join(" ")))
+    }
+
+    /// Returns `true` for developer and public betas.
+    pub fn is_beta(&self) -> bool {
+        matches!(
+            self,
+            ReleaseStage::DeveloperBeta(_) | ReleaseStage::PublicBeta(_)
+        )
+    }
+
+    /// Returns `true` for anything other than a final release.
+    pub fn is_prerelease(&self) -> bool {
+        *self != ReleaseStage::Release
+    }
+
+    /// Position of the stage in the release cycle, ignoring seed numbers.
+    fn rank(&self) -> u8 {
+        match self {
+            ReleaseStage::DeveloperBeta(_) | ReleaseStage::PublicBeta(_) => 0,
+            ReleaseStage::ReleaseCandidate(_) => 1,
+            ReleaseStage::Release => 2,
+            ReleaseStage::Unknown(_) => 3,
+        }
+    }
+}
+
+impl Ord for ReleaseStage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use ReleaseStage::*;
+
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| match (self, other) {
+                (DeveloperBeta(a), DeveloperBeta(b))
+                | (PublicBeta(a), PublicBeta(b))
+                | (ReleaseCandidate(a), ReleaseCandidate(b)) => a.cmp(b),
+                // A public beta ships just after the developer beta with the same number.
+                (DeveloperBeta(a), PublicBeta(b)) => a.cmp(b).then(Ordering::Less),
+                (PublicBeta(a), DeveloperBeta(b)) => a.cmp(b).then(Ordering::Greater),
+                (Unknown(a), Unknown(b)) => a.cmp(b),
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for ReleaseStage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for ReleaseStage {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseStage::DeveloperBeta(number) => write!(formatter, "beta {}", number),
+            ReleaseStage::PublicBeta(number) => write!(formatter, "Public Beta {}", number),
+            ReleaseStage::ReleaseCandidate(1) => write!(formatter, "RC"),
+            ReleaseStage::ReleaseCandidate(number) => write!(formatter, "RC {}", number),
+            ReleaseStage::Release => write!(formatter, "Release"),
+            ReleaseStage::Unknown(words) => write!(formatter, "{}", words),
+        }
+    }
+}
+
+impl FromStr for ReleaseStage {
+    type Err = ();
+
+    /// Parses a stage name. Never fails; unrecognized text becomes `Unknown`.
+    fn from_str(stage: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = stage.split_whitespace().collect();
+        match words.as_slice() {
+            [word] if word.eq_ignore_ascii_case("release") => Ok(ReleaseStage::Release),
+            _ => Ok(ReleaseStage::from_words(&words)),
+        }
+    }
+}
+
+impl Serialize for ReleaseStage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReleaseStage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let stage = String::deserialize(deserializer)?;
+        Ok(stage.parse().unwrap_or(ReleaseStage::Unknown(stage)))
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_stage_from_words() {
+    assert_eq!(ReleaseStage::from_words(&[]), ReleaseStage::Release);
+    assert_eq!(
+        ReleaseStage::from_words(&["beta"]),
+        ReleaseStage::DeveloperBeta(1)
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["beta", "3"]),
+        ReleaseStage::DeveloperBeta(3)
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["Public", "Beta", "2"]),
+        ReleaseStage::PublicBeta(2)
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["RC"]),
+        ReleaseStage::ReleaseCandidate(1)
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["RC", "2"]),
+        ReleaseStage::ReleaseCandidate(2)
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["Release", "Candidate"]),
+        ReleaseStage::ReleaseCandidate(1)
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["Update"]),
+        ReleaseStage::Unknown("Update".to_string())
+    );
+    assert_eq!(
+        ReleaseStage::from_words(&["beta", "three"]),
+        ReleaseStage::Unknown("beta three".to_string())
+    );
+}
+
+#[test]
+fn test_stage_ordering() {
+    let mut stages = vec![
+        ReleaseStage::Release,
+        ReleaseStage::ReleaseCandidate(2),
+        ReleaseStage::DeveloperBeta(10),
+        ReleaseStage::ReleaseCandidate(1),
+        ReleaseStage::PublicBeta(2),
+        ReleaseStage::DeveloperBeta(2),
+        ReleaseStage::Unknown("Update".to_string()),
+    ];
+    stages.sort();
+
+    assert_eq!(
+        stages,
+        vec![
+            ReleaseStage::DeveloperBeta(2),
+            ReleaseStage::PublicBeta(2),
+            ReleaseStage::DeveloperBeta(10),
+            ReleaseStage::ReleaseCandidate(1),
+            ReleaseStage::ReleaseCandidate(2),
+            ReleaseStage::Release,
+            ReleaseStage::Unknown("Update".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_stage_display_round_trip() {
+    for stage in [
+        ReleaseStage::DeveloperBeta(3),
+        ReleaseStage::PublicBeta(2),
+        ReleaseStage::ReleaseCandidate(1),
+        ReleaseStage::ReleaseCandidate(2),
+        ReleaseStage::Release,
+        ReleaseStage::Unknown("Update".to_string()),
+    ] {
+        assert_eq!(stage.to_string().parse::<ReleaseStage>().unwrap(), stage);
+
+        let json = serde_json::to_string(&stage).unwrap();
+        assert_eq!(serde_json::from_str::<ReleaseStage>(&json).unwrap(), stage);
+    }
+}
+
+#[test]
+fn test_stage_display_normalization() {
+    let display = |stage: &str| stage.parse::<ReleaseStage>().unwrap().to_string();
+
+    assert_eq!(display("beta"), "beta 1");
+    assert_eq!(display("developer beta 1"), "beta 1");
+    assert_eq!(display("Public Beta"), "Public Beta 1");
+    assert_eq!(display("Release Candidate"), "RC");
+    assert_eq!(display("RC 1"), "RC");
+
+    // The normalized name parses back to the same stage
+    assert_eq!(
+        display("beta").parse::<ReleaseStage>().unwrap(),
+        ReleaseStage::DeveloperBeta(1)
+    );
+}
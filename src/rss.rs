@@ -0,0 +1,318 @@
+//!
+//! rss.rs
+//!
+
+use crate::RELEASES_FEED;
+use crate::article::Article;
+use crate::body;
+use crate::error::{Error, Result};
+use crate::link;
+use crate::parse::{self, ParseResult, ParseWarning};
+use crate::selectors::SelectorList;
+use crate::software_release::SoftwareRelease;
+use crate::title;
+use chrono::{DateTime, NaiveDate};
+use roxmltree::{Document, Node};
+use scraper::{Html, Selector};
+use url::Url;
+
+/// The Apple Developer software releases RSS feed.
+pub const APPLE_DEV_RELEASES_RSS: &str =
+    "https://developer.apple.com/news/releases/rss/releases.rss";
+
+/// Finds articles in an RSS 2.0 feed.
+///
+/// Each `<item>` becomes an [`Article`]: `title` is the title, `pubDate` the date, `link` the
+/// permalink, and the `description` HTML its body and links, from which the release notes URL is
+/// taken. Malformed items do not stop parsing; they are reported in [`ParseResult::warnings`].
+///
+/// # Arguments
+///
+/// - `content` - The XML to parse.
+///
+/// # Returns
+///
+/// The parsed articles and any per-item warnings, or `Error::Feed` if the document is not an RSS
+/// feed.
+///
+/// # Example
+///
+/// ```
+/// let xml = r#"<?xml version="1.0"?>
+/// <rss version="2.0"><channel>
+///     <item>
+///         <title>iOS 16.1.2 (20B110)</title>
+///         <link>https://developer.apple.com/news/releases/?id=11302022a</link>
+///         <pubDate>Wed, 30 Nov 2022 10:00:00 PST</pubDate>
+///     </item>
+/// </channel></rss>
+/// "#;
+///
+/// let result = apple_releases::rss::parse_feed(xml.to_string()).unwrap();
+/// assert_eq!(result.articles.len(), 1);
+/// assert_eq!(result.articles[0].title, "iOS 16.1.2 (20B110)");
+/// ```
+pub fn parse_feed(content: String) -> Result<ParseResult> {
+    parse_feed_with(content, &RELEASES_FEED)
+}
+
+/// Finds articles in an RSS 2.0 feed read from `feed_url`.
+///
+/// # Arguments
+///
+/// - `content` - The XML to parse.
+/// - `feed_url` - URL of the feed, which relative links are resolved against.
+///
+/// # Returns
+///
+/// The parsed articles and any per-item warnings, or `Error::Feed` if the document is not an RSS
+/// feed.
+pub fn parse_feed_with(content: String, feed_url: &Url) -> Result<ParseResult> {
+    let document = Document::parse(&content).map_err(|error| Error::Feed(error.to_string()))?;
+    let channel = document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("channel"))
+        .filter(|_| document.root_element().has_tag_name("rss"))
+        .ok_or_else(|| Error::Feed("No RSS channel found".to_string()))?;
+
+    let mut result = ParseResult::default();
+    for item in channel.children().filter(|node| node.has_tag_name("item")) {
+        let xml = content[item.range()].to_string();
+
+        let raw_title = child_text(&item, "title");
+        let title = raw_title.as_deref().map(title::title_text);
+        let Some(title) = title.filter(|title| !title.is_empty()) else {
+            result.warnings.push(ParseWarning {
+                title: None,
+                html: xml,
+                error: Error::Feed("No title found".to_string()),
+            });
+            continue;
+        };
+
+        let date = match parse_item_date(child_text(&item, "pubDate")) {
+            Ok(date) => date,
+            Err(error) => {
+                result.warnings.push(ParseWarning {
+                    title: Some(title),
+                    html: xml,
+                    error,
+                });
+                continue;
+            }
+        };
+
+        let link = child_text(&item, "link");
+        let permalink = match link.map(|link| crate::url::resolve(feed_url, &link)) {
+            Some(Ok(url)) => Some(url),
+            Some(Err(error)) => {
+                result.warnings.push(ParseWarning {
+                    title: Some(title.clone()),
+                    html: xml.clone(),
+                    error,
+                });
+                None
+            }
+            None => None,
+        };
+
+        let description =
+            Html::parse_fragment(&child_text(&item, "description").unwrap_or_default());
+        let links = parse::build_links(feed_url, description_links(&description), |error| {
+            result.warnings.push(ParseWarning {
+                title: Some(title.clone()),
+                html: xml.clone(),
+                error,
+            })
+        });
+
+        let software_release = match SoftwareRelease::parse(&title) {
+            Ok(release) => release,
+            Err(error) => {
+                result.warnings.push(ParseWarning {
+                    title: Some(title.clone()),
+                    html: xml,
+                    error,
+                });
+                None
+            }
+        };
+
+        result.articles.push(Article {
+            title_html: raw_title.filter(|raw| *raw != title),
+            title,
+            software_release,
+            date,
+            release_notes_url: link::release_notes_url(&links, feed_url),
+            permalink,
+            links,
+            body: body::from_html(&description.root_element(), feed_url),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Returns `true` if `content` looks like an RSS feed rather than an HTML page.
+pub fn is_feed(content: &str) -> bool {
+    let start = content.trim_start();
+    start.starts_with("<?xml") || start.starts_with("<rss")
+}
+
+/// Returns the trimmed text of the first child element named `name`, if it is not empty.
+fn child_text(node: &Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// Parses an RFC 2822 `pubDate`, keeping the date in the feed's own time zone.
+fn parse_item_date(date: Option<String>) -> Result<NaiveDate> {
+    let date = date.ok_or_else(|| Error::Feed("No date found".to_string()))?;
+    DateTime::parse_from_rfc2822(&date)
+        .map(|date| date.date_naive())
+        .map_err(|source| Error::Date {
+            value: date.clone(),
+            source,
+        })
+}
+
+/// Finds the text and href of each link in an item description.
+fn description_links(description: &Html) -> Vec<(String, String)> {
+    let links = SelectorList::single(Selector::parse("a[href]").unwrap());
+    parse::parse_links(&description.root_element(), &links)
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+const FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Releases - Apple Developer</title>
+    <link>https://developer.apple.com/news/releases/</link>
+    <item>
+      <title>iOS 17.2 beta 4 (21C5054b)</title>
+      <link>https://developer.apple.com/news/releases/?id=11282023a</link>
+      <guid>https://developer.apple.com/news/releases/?id=11282023a</guid>
+      <description><![CDATA[<p><a href="/download/">View downloads</a></p>
+        <p><a href="/go/?id=ios-17.2-rn">Release Notes</a></p>]]></description>
+      <pubDate>Tue, 28 Nov 2023 10:00:00 PST</pubDate>
+    </item>
+    <item>
+      <title>Upcoming requirements for app distribution</title>
+      <link>https://developer.apple.com/news/releases/?id=11272023a</link>
+      <description>Starting in spring 2024&#8230;</description>
+      <pubDate>Mon, 27 Nov 2023 18:30:00 GMT</pubDate>
+    </item>
+    <item>
+      <title>Xcode 15.1 beta 3 (15C5059c)</title>
+      <pubDate>sometime in November</pubDate>
+    </item>
+    <item>
+      <link>https://developer.apple.com/news/releases/?id=11262023a</link>
+    </item>
+  </channel>
+</rss>
+"#;
+
+#[test]
+fn test_parse_feed() {
+    use crate::product::Product;
+
+    let result = parse_feed(FEED.to_string()).unwrap();
+    assert_eq!(result.articles.len(), 2);
+
+    let article = &result.articles[0];
+    assert_eq!(article.title, "iOS 17.2 beta 4 (21C5054b)");
+    assert_eq!(article.date, NaiveDate::from_ymd_opt(2023, 11, 28).unwrap());
+    assert_eq!(
+        article.release_notes_url.as_ref().unwrap().as_str(),
+        "https://developer.apple.com/go/?id=ios-17.2-rn"
+    );
+    assert_eq!(
+        article.permalink.as_ref().unwrap().as_str(),
+        "https://developer.apple.com/news/releases/?id=11282023a"
+    );
+    assert_eq!(article.links.len(), 2);
+    assert_eq!(article.links[0].kind, crate::link::LinkKind::Downloads);
+    assert_eq!(
+        article.software_release.as_ref().unwrap().product,
+        Product::iOS
+    );
+
+    let article = &result.articles[1];
+    assert_eq!(article.software_release, None);
+    assert_eq!(
+        article.body.as_ref().unwrap().text,
+        "Starting in spring 2024…"
+    );
+    assert_eq!(article.release_notes_url, None);
+
+    assert_eq!(result.warnings.len(), 2);
+    assert!(matches!(result.warnings[0].error, Error::Date { .. }));
+    assert_eq!(
+        result.warnings[0].title.as_deref(),
+        Some("Xcode 15.1 beta 3 (15C5059c)")
+    );
+    assert!(matches!(result.warnings[1].error, Error::Feed(_)));
+    assert!(result.warnings[1].html.starts_with("<item>"));
+}
+
+#[test]
+fn test_parse_feed_against_feed_url() {
+    let feed_url = Url::parse("http://localhost:8080/releases.rss").unwrap();
+    let feed = FEED.replace(
+        "<link>https://developer.apple.com/news/releases/?id=11282023a</link>",
+        "<link>/news/releases/?id=11282023a</link>",
+    );
+    let result = parse_feed_with(feed, &feed_url).unwrap();
+
+    let article = &result.articles[0];
+    assert_eq!(
+        article.permalink.as_ref().unwrap().as_str(),
+        "http://localhost:8080/news/releases/?id=11282023a"
+    );
+    assert_eq!(
+        article.links[0].url.as_str(),
+        "http://localhost:8080/download/"
+    );
+    assert_eq!(
+        article.release_notes_url.as_ref().unwrap().as_str(),
+        "http://localhost:8080/go/?id=ios-17.2-rn"
+    );
+    assert!(
+        article
+            .body
+            .as_ref()
+            .unwrap()
+            .markdown
+            .contains("(http://localhost:8080/download/)")
+    );
+}
+
+#[test]
+fn test_parse_invalid_feed() {
+    for content in [
+        "",
+        "<html><body></body></html>",
+        "<rss><channel>",
+        "<feed/>",
+    ] {
+        assert!(
+            matches!(parse_feed(content.to_string()), Err(Error::Feed(_))),
+            "{}",
+            content
+        );
+    }
+}
+
+#[test]
+fn test_is_feed() {
+    assert!(is_feed(FEED));
+    assert!(is_feed("\n<rss version=\"2.0\"></rss>"));
+    assert!(!is_feed("<!DOCTYPE html><html></html>"));
+}
@@ -2,30 +2,187 @@
 //! selectors.rs
 //!
 
-use scraper::Selector;
+use crate::config::SelectorConfig;
+use crate::error::{Error, Result};
+use scraper::{ElementRef, Selector};
+
+/// Built-in selectors for each part of the page, tried after any configured ones.
+const ARTICLE: &[&str] = &["section.article-content-container"];
+const TITLE: &[&str] = &["a.article-title h2"];
+const DATE: &[&str] = &["p.article-date"];
+const BODY: &[&str] = &["span.article-text"];
+const LINKS: &[&str] = &["span.article-text a[href]"];
+const PERMALINK: &[&str] = &[".social-share-container [data-href]"];
+const NEXT_PAGE: &[&str] = &[
+    r#"link[rel="next"]"#,
+    r#"a[rel="next"]"#,
+    ".pagination a.next",
+    "a.pagination-next",
+];
 
 /// Collection of scraper Selectors - https://docs.rs/scraper/latest/scraper/
-pub(crate) struct Selectors {
+///
+/// Each part of the page has a list of selectors, tried in order until one matches. The built-in
+/// lists follow the current releases page; [`Selectors::with_config`] puts selectors from the
+/// configuration file or command line in front of them, so a redesign can be handled without a
+/// new release.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::config::SelectorConfig;
+/// use apple_releases::selectors::Selectors;
+///
+/// let mut config = SelectorConfig::default();
+/// config.title = vec!["h2.release-title".to_string()];
+/// let selectors = Selectors::with_config(&config).unwrap();
+///
+/// let html = r#"
+/// <section class="article-content-container">
+///     <h2 class="release-title">iOS 16.1.2 (20B110)</h2>
+///     <p class="article-date">November 30, 2022</p>
+/// </section>
+/// "#;
+/// let page_url = url::Url::parse(apple_releases::APPLE_DEV_RELEASES).unwrap();
+/// let result = apple_releases::parse::parse_articles_with(html.to_string(), &selectors, &page_url);
+/// assert_eq!(result.articles[0].title, "iOS 16.1.2 (20B110)");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Selectors {
     /// Parses the article container, the top-level containing values of interest.
-    pub(crate) article: Selector,
+    pub(crate) article: SelectorList,
 
     /// Parses the article title.
-    pub(crate) title: Selector,
+    pub(crate) title: SelectorList,
 
     /// Parses the article date.
-    pub(crate) date: Selector,
+    pub(crate) date: SelectorList,
+
+    /// Parses the article body.
+    pub(crate) body: SelectorList,
+
+    /// Parses the links in the article body.
+    pub(crate) links: SelectorList,
+
+    /// Parses the share buttons, whose `data-href` is the article's permalink.
+    pub(crate) permalink: SelectorList,
 
-    /// Parses the short release notes URL.
-    pub(crate) release_notes_short_url: Selector,
+    /// Parses the link to the next (older) page of articles.
+    pub(crate) next_page: SelectorList,
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        Self::with_config(&SelectorConfig::default()).unwrap()
+    }
 }
 
 impl Selectors {
-    pub(crate) fn new() -> Self {
-        Self {
-            article: Selector::parse(r#"section.article-content-container"#).unwrap(),
-            title: Selector::parse(r#"a.article-title h2"#).unwrap(),
-            date: Selector::parse(r#"p.article-date"#).unwrap(),
-            release_notes_short_url: Selector::parse(r#"span.article-text ul.links-stacked li:nth-child(2) a.more"#).unwrap(),
+    /// Creates the built-in selectors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates selectors which try those in `config` before the built-in ones.
+    ///
+    /// # Arguments
+    ///
+    /// - `config` - Extra selectors for each part of the page.
+    ///
+    /// # Returns
+    ///
+    /// The selectors, or `Error::Config` naming the first selector which is not valid CSS.
+    pub fn with_config(config: &SelectorConfig) -> Result<Self> {
+        Ok(Self {
+            article: SelectorList::parse("article", &config.article, ARTICLE)?,
+            title: SelectorList::parse("title", &config.title, TITLE)?,
+            date: SelectorList::parse("date", &config.date, DATE)?,
+            body: SelectorList::parse("body", &config.body, BODY)?,
+            links: SelectorList::parse("links", &config.links, LINKS)?,
+            permalink: SelectorList::parse("permalink", &config.permalink, PERMALINK)?,
+            next_page: SelectorList::parse("next_page", &config.next_page, NEXT_PAGE)?,
+        })
+    }
+}
+
+/// Selectors for one part of the page, tried in order until one matches.
+#[derive(Debug, Clone)]
+pub(crate) struct SelectorList(Vec<Selector>);
+
+impl SelectorList {
+    /// Parses the configured selectors for `field`, followed by the built-in ones.
+    fn parse(field: &str, configured: &[String], built_in: &[&str]) -> Result<Self> {
+        let css = configured
+            .iter()
+            .map(String::as_str)
+            .chain(built_in.iter().copied());
+
+        let mut selectors = Vec::new();
+        for css in css {
+            selectors.push(Selector::parse(css).map_err(|error| {
+                Error::Config(format!(
+                    "selectors.{}: invalid selector '{}': {}",
+                    field, css, error
+                ))
+            })?);
         }
+        Ok(Self(selectors))
+    }
+
+    /// Wraps a single selector.
+    pub(crate) fn single(selector: Selector) -> Self {
+        Self(vec![selector])
     }
+
+    /// Returns the elements within `element` matched by the first selector which matches any.
+    pub(crate) fn select<'a>(&self, element: &ElementRef<'a>) -> Vec<ElementRef<'a>> {
+        self.0
+            .iter()
+            .map(|selector| element.select(selector).collect::<Vec<_>>())
+            .find(|matches| !matches.is_empty())
+            .unwrap_or_default()
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_selector_fallback() {
+    let config = SelectorConfig {
+        title: vec!["h3.headline".to_string()],
+        ..SelectorConfig::default()
+    };
+    let selectors = Selectors::with_config(&config).unwrap();
+
+    let fragment = scraper::Html::parse_fragment(
+        r#"<a class="article-title"><h2>Built in</h2></a><h3 class="headline">Configured</h3>"#,
+    );
+    let titles = selectors.title.select(&fragment.root_element());
+    assert_eq!(titles.len(), 1);
+    assert_eq!(titles[0].inner_html(), "Configured");
+
+    let fragment =
+        scraper::Html::parse_fragment(r#"<a class="article-title"><h2>Built in</h2></a>"#);
+    let titles = selectors.title.select(&fragment.root_element());
+    assert_eq!(titles[0].inner_html(), "Built in");
+
+    let fragment = scraper::Html::parse_fragment("<h4>Neither</h4>");
+    assert!(selectors.title.select(&fragment.root_element()).is_empty());
+}
+
+#[test]
+fn test_invalid_selector() {
+    let config = SelectorConfig {
+        date: vec!["p.date".to_string(), "p[".to_string()],
+        ..SelectorConfig::default()
+    };
+    let error = Selectors::with_config(&config).unwrap_err();
+    assert!(matches!(error, Error::Config(_)));
+    assert!(
+        error
+            .to_string()
+            .contains("selectors.date: invalid selector 'p['"),
+        "{}",
+        error
+    );
 }
@@ -2,63 +2,306 @@
 //! software_release.rs
 //!
 
+use crate::apple_build::AppleBuild;
+use crate::error::{Error, Result};
 use crate::product::Product;
-use semver::Version;
-use std::str::FromStr;
+use crate::release_stage::ReleaseStage;
+use crate::title::parse_title;
+use semver::{BuildMetadata, Prerelease, Version};
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Ordering;
 
-pub(crate) struct SoftwareRelease {
+/// A software release of a known product.
+///
+/// # JSON
+///
+/// ```json
+/// {
+///   "product": "iOS",
+///   "marketing_name": null,
+///   "version": {
+///     "major": 16,
+///     "minor": 2,
+///     "patch": 0,
+///     "prerelease": "beta-3",
+///     "build": "20C5049e"
+///   },
+///   "stage": "beta 3",
+///   "apple_build": "20C5049e"
+/// }
+/// ```
+///
+/// `marketing_name`, `prerelease`, `build` and `apple_build` are `null` when absent. `stage` defaults
+/// to `"Release"` when absent. `prerelease` is written from `stage`, as by
+/// [`SoftwareRelease::prerelease`], and ignored when read.
+///
+/// Releases of the same product are ordered by version number, then by [`ReleaseStage`], so
+/// `beta 2 < beta 10 < RC < Release`, then by build, so a re-spin sorts after the build it
+/// replaces. Releases of different products are not comparable.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SoftwareRelease {
     /// Known product type.
-    pub(crate) product: Product,
+    pub product: Product,
 
-    /// Product version.
-    pub(crate) version: Version,
+    /// Marketing name, e.g. `Sonoma` in `macOS Sonoma 14.1`.
+    #[serde(default)]
+    pub marketing_name: Option<String>,
+
+    /// Product version: the numeric parts, with the build number as build metadata. The semver
+    /// prerelease is always empty, since the stage is kept in `stage`; see
+    /// [`SoftwareRelease::semver`] for a version string which includes it.
+    #[serde(with = "version_serde")]
+    pub version: Version,
+
+    /// Stage in the beta cycle.
+    #[serde(default)]
+    pub stage: ReleaseStage,
+
+    /// Build number from the title, if present and well-formed.
+    #[serde(default)]
+    pub apple_build: Option<AppleBuild>,
 }
 
 impl SoftwareRelease {
     /// Attempts to parse `title` as a software release with version.
-    pub(crate) fn software_release(title: String) -> Option<SoftwareRelease> {
+    ///
+    /// Returns `None` if the title is not a release of a known product or its version is
+    /// malformed. Use [`SoftwareRelease::parse`] to find out why a title was rejected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apple_releases::{Product, SoftwareRelease};
+    ///
+    /// let release = SoftwareRelease::software_release("iOS 16.1.2 (20B110)".to_string()).unwrap();
+    /// assert_eq!(release.product, Product::iOS);
+    /// assert_eq!(release.version.to_string(), "16.1.2+20B110");
+    /// ```
+    #[allow(clippy::self_named_constructors)]
+    pub fn software_release(title: String) -> Option<SoftwareRelease> {
+        Self::parse(&title).ok().flatten()
+    }
+
+    /// Parses `title` as a software release with version.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(release))` if the title is a release of a product.
+    /// - `Ok(None)` if the title does not start with a product, or is an announcement naming a
+    ///   product without a version, e.g. `Xcode Cloud now available`.
+    /// - `Err(Error::Version)` if the title names a known product but the version is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apple_releases::SoftwareRelease;
+    ///
+    /// assert!(SoftwareRelease::parse("iOS 16.2 beta 3 (20C5049e)").unwrap().is_some());
+    /// assert!(SoftwareRelease::parse("Upcoming requirements").unwrap().is_none());
+    /// assert!(SoftwareRelease::parse("Xcode Cloud now available").unwrap().is_none());
+    /// assert!(SoftwareRelease::parse("iOS 16.x").is_err());
+    /// ```
+    pub fn parse(title: &str) -> Result<Option<SoftwareRelease>> {
         // iOS 16.2 beta 4 (20C5058d)
         // iOS 16.1.2 (20B110)
+        // macOS Sonoma 14.1 RC 2 (23B73)
+        // Xcode 15 Release Candidate
+
+        let Some(parsed) = parse_title(title)? else {
+            return Ok(None);
+        };
+
+        let invalid = |message: String| Error::Version {
+            value: title.to_string(),
+            message,
+        };
+
+        let (major, minor, patch) = parsed.version;
+        let mut version = Version::new(major, minor, patch);
+        if let Some(build) = parsed.build {
+            version.build =
+                BuildMetadata::new(build).map_err(|error| invalid(error.to_string()))?;
+        }
+
+        Ok(Some(SoftwareRelease {
+            product: parsed.product,
+            marketing_name: parsed.marketing_name,
+            stage: ReleaseStage::from_words(&parsed.stage_words),
+            apple_build: parsed.build.and_then(|build| build.parse().ok()),
+            version,
+        }))
+    }
+}
+
+impl SoftwareRelease {
+    /// Returns the stage as a semver prerelease identifier, e.g. `beta-3` or `RC-2`, or `None` for a
+    /// final release.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apple_releases::SoftwareRelease;
+    ///
+    /// let release = SoftwareRelease::software_release("iOS 16.2 beta 3 (20C5049e)".to_string()).unwrap();
+    /// assert_eq!(release.prerelease().as_deref(), Some("beta-3"));
+    /// ```
+    pub fn prerelease(&self) -> Option<String> {
+        if !self.stage.is_prerelease() {
+            return None;
+        }
+
+        let prerelease = self
+            .stage
+            .to_string()
+            .split_whitespace()
+            .map(|word| word.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', ""))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        (!prerelease.is_empty()).then_some(prerelease)
+    }
+
+    /// Returns the version with the stage as its semver prerelease, e.g. `16.2.0-beta-3+20C5049e`,
+    /// for display. Use [`PartialOrd`] rather than comparing these, since semver orders stages
+    /// lexically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apple_releases::SoftwareRelease;
+    ///
+    /// let release = SoftwareRelease::software_release("iOS 16.2 beta 3 (20C5049e)".to_string()).unwrap();
+    /// assert_eq!(release.version.to_string(), "16.2.0+20C5049e");
+    /// assert_eq!(release.semver().to_string(), "16.2.0-beta-3+20C5049e");
+    /// ```
+    pub fn semver(&self) -> Version {
+        let mut version = self.version.clone();
+        if let Some(pre) = self
+            .prerelease()
+            .and_then(|prerelease| Prerelease::new(&prerelease).ok())
+        {
+            version.pre = pre;
+        }
+        version
+    }
+
+    /// Returns `true` if `other` is the same product, version and stage with a different build, as
+    /// happens when Apple re-spins a release.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use apple_releases::SoftwareRelease;
+    ///
+    /// let release = SoftwareRelease::software_release("iOS 16.0 (20A362)".to_string()).unwrap();
+    /// let respin = SoftwareRelease::software_release("iOS 16.0 (20A371)".to_string()).unwrap();
+    /// assert!(respin.is_respin_of(&release));
+    /// ```
+    pub fn is_respin_of(&self, other: &SoftwareRelease) -> bool {
+        let numbers = |release: &SoftwareRelease| {
+            (
+                release.version.major,
+                release.version.minor,
+                release.version.patch,
+            )
+        };
+
+        self.product == other.product
+            && numbers(self) == numbers(other)
+            && self.stage == other.stage
+            && matches!((&self.apple_build, &other.apple_build), (Some(a), Some(b)) if a != b)
+    }
+}
+
+impl Serialize for SoftwareRelease {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        /// The serialized fields, with the stage as the version's prerelease.
+        #[derive(Serialize)]
+        struct Fields<'a> {
+            product: &'a Product,
+            marketing_name: &'a Option<String>,
+            #[serde(serialize_with = "version_serde::serialize")]
+            version: Version,
+            stage: &'a ReleaseStage,
+            apple_build: &'a Option<AppleBuild>,
+        }
+
+        Fields {
+            product: &self.product,
+            marketing_name: &self.marketing_name,
+            version: self.semver(),
+            stage: &self.stage,
+            apple_build: &self.apple_build,
+        }
+        .serialize(serializer)
+    }
+}
 
-        let mut tokens = title.split(' ');
-        // for token in tokens {}
-        let product_name = tokens.next().unwrap();
-
-        Product::from_str(product_name)
-            .map(|product| {
-                let mut version_string = tokens.next().unwrap().to_string();
-                let mut tmp_string: &str = tokens.next().unwrap();
-                while !tmp_string.contains('(') {
-                    version_string = format!("{}-{}", version_string, tmp_string);
-                    tmp_string = tokens.next().unwrap();
-                }
-
-                if tmp_string.contains('(') {
-                    // Extract what's inside the parentheses, handling case where closing parenthesis is missing
-                    let start = tmp_string.find('(').unwrap() + 1;
-                    let end = tmp_string.rfind(')').unwrap_or(tmp_string.len());
-                    
-                    // Make sure start is valid and doesn't exceed the string length
-                    if start < tmp_string.len() {
-                        let build_string = &tmp_string[start..end];
-                        version_string = format!("{}+{}", version_string, build_string);
-                    }
-                }
-
-                // Sanitize the version string to remove problematic characters
-                // Remove any stray parentheses that might interfere with semver parsing
-                let sanitized_version = version_string.replace("(", "").replace(")", "");
-
-                // Try to parse the version, returning None if it fails
-                match lenient_semver::parse(&sanitized_version) {
-                    Ok(version) => Some(SoftwareRelease { product, version }),
-                    Err(_) => {
-                        eprintln!("Warning: Failed to parse version string: {}", sanitized_version);
-                        None
-                    }
-                }
-            })
-            .unwrap_or(None)
+impl PartialOrd for SoftwareRelease {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.product != other.product {
+            return None;
+        }
+
+        let numbers = |release: &SoftwareRelease| {
+            (
+                release.version.major,
+                release.version.minor,
+                release.version.patch,
+            )
+        };
+        // The remaining fields break ties, so only equal releases compare as equal
+        Some(
+            numbers(self)
+                .cmp(&numbers(other))
+                .then_with(|| self.stage.cmp(&other.stage))
+                .then_with(|| self.apple_build.cmp(&other.apple_build))
+                .then_with(|| self.version.cmp(&other.version))
+                .then_with(|| self.marketing_name.cmp(&other.marketing_name)),
+        )
+    }
+}
+
+/// Serializes a semver `Version` as an object with separate fields rather than a single string.
+mod version_serde {
+    use semver::{BuildMetadata, Version};
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The prerelease is ignored when read, since the stage is stored separately.
+    #[derive(Serialize, Deserialize)]
+    struct VersionFields {
+        major: u64,
+        minor: u64,
+        patch: u64,
+        #[serde(default, skip_deserializing)]
+        prerelease: Option<String>,
+        build: Option<String>,
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        version: &Version,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        VersionFields {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            prerelease: (!version.pre.is_empty()).then(|| version.pre.to_string()),
+            build: (!version.build.is_empty()).then(|| version.build.to_string()),
+        }
+        .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Version, D::Error> {
+        let fields = VersionFields::deserialize(deserializer)?;
+        let mut version = Version::new(fields.major, fields.minor, fields.patch);
+        if let Some(build) = fields.build {
+            version.build = BuildMetadata::new(&build).map_err(D::Error::custom)?;
+        }
+        Ok(version)
     }
 }
 
@@ -88,15 +331,15 @@ fn test_software_prerelease() {
     assert_eq!(release.version.major, 16);
     assert_eq!(release.version.minor, 2);
     assert_eq!(release.version.patch, 0);
-    assert_eq!(
-        release.version.pre,
-        semver::Prerelease::new("beta-3").unwrap()
-    );
+    assert_eq!(release.version.pre, semver::Prerelease::EMPTY);
+    assert_eq!(release.stage, ReleaseStage::DeveloperBeta(3));
+    assert_eq!(release.prerelease().as_deref(), Some("beta-3"));
     assert_eq!(
         release.version.build,
         semver::BuildMetadata::new("20C5049e").unwrap()
     );
-    assert_eq!(release.version.to_string(), "16.2.0-beta-3+20C5049e");
+    assert_eq!(release.version.to_string(), "16.2.0+20C5049e");
+    assert_eq!(release.semver().to_string(), "16.2.0-beta-3+20C5049e");
 }
 
 #[test]
@@ -114,4 +357,231 @@ fn test_software_release() {
         semver::BuildMetadata::new("20B110").unwrap()
     );
     assert_eq!(release.version.to_string(), "16.1.2+20B110");
+    assert_eq!(release.prerelease(), None);
+    assert_eq!(release.semver(), release.version);
+}
+
+#[test]
+fn test_software_release_unknown_product() {
+    let release = SoftwareRelease::parse("Upcoming requirements for app distribution").unwrap();
+    assert!(release.is_none());
+
+    let release = SoftwareRelease::parse("Final Cut Pro 11 beta 2")
+        .unwrap()
+        .unwrap();
+    assert_eq!(release.product, Product::Other("Final Cut Pro".to_string()));
+}
+
+#[test]
+fn test_software_release_without_build() {
+    let release = SoftwareRelease::parse("Xcode 15 Release Candidate")
+        .unwrap()
+        .unwrap();
+    assert_eq!(release.product, Product::Xcode);
+    assert_eq!(release.version.major, 15);
+    assert_eq!(release.version.build, semver::BuildMetadata::EMPTY);
+}
+
+#[test]
+fn test_software_release_malformed_version() {
+    assert!(matches!(
+        SoftwareRelease::parse("iOS 16.x"),
+        Err(Error::Version { .. })
+    ));
+    assert!(matches!(
+        SoftwareRelease::parse("iOS sixteen (20B110)"),
+        Err(Error::Version { .. })
+    ));
+    assert!(SoftwareRelease::software_release("iOS sixteen (20B110)".to_string()).is_none());
+}
+
+#[test]
+fn test_software_release_json() {
+    let release = SoftwareRelease::software_release("iOS 16.2 beta 3 (20C5049e)".into()).unwrap();
+    let json = serde_json::to_value(&release).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "product": "iOS",
+            "marketing_name": null,
+            "version": {
+                "major": 16,
+                "minor": 2,
+                "patch": 0,
+                "prerelease": "beta-3",
+                "build": "20C5049e",
+            },
+            "stage": "beta 3",
+            "apple_build": "20C5049e",
+        })
+    );
+
+    let decoded: SoftwareRelease = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, release);
+
+    // The stage is read from `stage`, not the prerelease
+    let mut json = serde_json::to_value(&release).unwrap();
+    json["version"]["prerelease"] = "RC-2".into();
+    let decoded: SoftwareRelease = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, release);
+    assert_eq!(decoded.version.pre, semver::Prerelease::EMPTY);
+}
+
+#[test]
+fn test_software_release_json_without_prerelease() {
+    let release = SoftwareRelease::software_release("Xcode 14.1".into()).unwrap();
+    let json = serde_json::to_value(&release).unwrap();
+
+    assert_eq!(json["stage"], "Release");
+    assert_eq!(json["version"]["prerelease"], serde_json::Value::Null);
+    assert_eq!(json["version"]["build"], serde_json::Value::Null);
+
+    let decoded: SoftwareRelease = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, release);
+}
+
+#[test]
+fn test_software_release_stage() {
+    let stage = |title: &str| SoftwareRelease::parse(title).unwrap().unwrap().stage;
+
+    assert_eq!(stage("iOS 16.1.2 (20B110)"), ReleaseStage::Release);
+    assert_eq!(
+        stage("iOS 16.2 beta 3 (20C5049e)"),
+        ReleaseStage::DeveloperBeta(3)
+    );
+    assert_eq!(
+        stage("macOS 13.1 RC 2 (22C65)"),
+        ReleaseStage::ReleaseCandidate(2)
+    );
+    assert_eq!(
+        stage("Xcode 15 Release Candidate"),
+        ReleaseStage::ReleaseCandidate(1)
+    );
+}
+
+#[test]
+fn test_software_release_ordering() {
+    let release = |title: &str| SoftwareRelease::parse(title).unwrap().unwrap();
+
+    let beta_2 = release("iOS 16.2 beta 2 (20C5032e)");
+    let beta_10 = release("iOS 16.2 beta 10 (20C5099e)");
+    let rc = release("iOS 16.2 RC (20C65)");
+    let ga = release("iOS 16.2 (20C65)");
+    let next_beta = release("iOS 16.3 beta (20D5024e)");
+
+    assert!(beta_2 < beta_10);
+    assert!(beta_10 < rc);
+    assert!(rc < ga);
+    assert!(ga < next_beta);
+
+    let xcode = release("Xcode 14.2 (14C18)");
+    assert_eq!(xcode.partial_cmp(&ga), None);
+
+    // Rapid Security Responses and updates ship after the release they patch
+    let release_16_4_1 = release("iOS 16.4.1 (20E252)");
+    let rsr = release("iOS 16.4.1 (a) (20E772520a)");
+    assert!(release("iOS 16.4.1 beta (20E5239a)") < rsr);
+    assert!(release_16_4_1 < rsr);
+    assert!(rsr < release("iOS 16.5 beta (20F5028e)"));
+
+    let macos = release("macOS 13.0.1 (22A400)");
+    let update = release("macOS Ventura 13.0.1 Update (22A400)");
+    assert!(release("macOS 13.0.1 RC (22A380)") < update);
+    assert!(macos < update);
+}
+
+#[test]
+fn test_software_release_apple_build() {
+    let release = SoftwareRelease::parse("iOS 16.2 beta 3 (20C5049e)")
+        .unwrap()
+        .unwrap();
+    let build = release.apple_build.unwrap();
+    assert_eq!(build.train, 20);
+    assert!(build.is_beta());
+
+    let release = SoftwareRelease::parse("Xcode 15 Release Candidate")
+        .unwrap()
+        .unwrap();
+    assert_eq!(release.apple_build, None);
+}
+
+#[test]
+fn test_software_release_respin() {
+    let release = |title: &str| SoftwareRelease::parse(title).unwrap().unwrap();
+
+    let ios = release("iOS 16.1 (20B82)");
+    let respin = release("iOS 16.1 (20B82a)");
+    let ipados = release("iPadOS 16.1 (20B82a)");
+    let next = release("iOS 16.1.1 (20B101)");
+
+    assert!(respin.is_respin_of(&ios));
+    assert!(!ios.is_respin_of(&ios));
+    assert!(!ipados.is_respin_of(&ios));
+    assert!(!next.is_respin_of(&ios));
+    assert_eq!(
+        ipados.apple_build.unwrap().cmp(&ios.apple_build.unwrap()),
+        std::cmp::Ordering::Greater
+    );
+    // Re-spins are ordered by build, consistently with equality
+    let first = release("iOS 16.1.2 (20B101)");
+    let respin = release("iOS 16.1.2 (20B110)");
+    assert!(respin.is_respin_of(&first));
+    assert_ne!(first, respin);
+    assert!(first < respin);
+    assert_eq!(
+        respin.partial_cmp(&first),
+        Some(std::cmp::Ordering::Greater)
+    );
+    assert_eq!(
+        first.partial_cmp(&first.clone()),
+        Some(std::cmp::Ordering::Equal)
+    );
+
+    let named = release("iOS Sixteen 16.1.2 (20B101)");
+    assert_ne!(named.partial_cmp(&first), Some(std::cmp::Ordering::Equal));
+}
+
+#[test]
+fn test_software_release_title_corpus() {
+    let corpus = include_str!("../tests/fixtures/titles.tsv");
+
+    for line in corpus.lines().filter(|line| !line.starts_with('#')) {
+        let columns: Vec<&str> = line.split('\t').collect();
+        let [title, product, marketing_name, version, stage, build] = columns[..] else {
+            panic!("malformed fixture line: {}", line);
+        };
+        let non_empty = |field: &str| (!field.is_empty()).then(|| field.to_string());
+
+        let release = SoftwareRelease::parse(title).unwrap();
+        if product.is_empty() {
+            assert!(release.is_none(), "{}", title);
+            continue;
+        }
+
+        let release = release.unwrap_or_else(|| panic!("not a release: {}", title));
+        assert_eq!(release.product.to_string(), product, "{}", title);
+        assert_eq!(
+            release.marketing_name,
+            non_empty(marketing_name),
+            "{}",
+            title
+        );
+        assert_eq!(
+            format!(
+                "{}.{}.{}",
+                release.version.major, release.version.minor, release.version.patch
+            ),
+            version,
+            "{}",
+            title
+        );
+        assert_eq!(release.stage.to_string(), stage, "{}", title);
+        assert_eq!(
+            release.apple_build.map(|build| build.to_string()),
+            non_empty(build),
+            "{}",
+            title
+        );
+    }
 }
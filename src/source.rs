@@ -0,0 +1,63 @@
+//!
+//! source.rs
+//!
+
+use crate::error::{Error, Result};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Where articles are read from.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Source {
+    /// The HTML releases page, scraped with CSS selectors.
+    Html,
+
+    /// The RSS feed.
+    Rss,
+
+    /// The HTML releases page, falling back to the RSS feed if no articles are found. A page which
+    /// turns out to be a feed is parsed as one.
+    #[default]
+    Auto,
+}
+
+impl Source {
+    /// Names accepted by `FromStr`, in the order they are listed in help text.
+    pub const NAMES: [&'static str; 3] = ["auto", "html", "rss"];
+}
+
+impl FromStr for Source {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Source::Auto),
+            "html" => Ok(Source::Html),
+            "rss" | "feed" => Ok(Source::Rss),
+            _ => Err(Error::Argument(format!("unknown source '{}'", name))),
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Source::Html => "html",
+            Source::Rss => "rss",
+            Source::Auto => "auto",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_parse_source() {
+    for name in Source::NAMES {
+        let source: Source = name.parse().unwrap();
+        assert_eq!(source.to_string(), name);
+    }
+    assert_eq!("RSS".parse::<Source>().unwrap(), Source::Rss);
+    assert!(matches!("atom".parse::<Source>(), Err(Error::Argument(_))));
+}
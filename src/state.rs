@@ -0,0 +1,213 @@
+//!
+//! state.rs
+//!
+
+use crate::article::Article;
+use crate::error::{Error, Result};
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// How long articles are remembered after the newest one seen. Apple's page covers a few months, so
+/// anything older cannot reappear as new.
+const RETENTION_DAYS: u64 = 366;
+
+/// Articles already reported, persisted between runs to find what is new.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::state::SeenState;
+/// use apple_releases::Article;
+/// use chrono::NaiveDate;
+///
+/// let article = Article {
+///     title: "iOS 16.1.2 (20B110)".to_string(),
+///     software_release: None,
+///     date: NaiveDate::from_ymd_opt(2022, 11, 30).unwrap(),
+///     release_notes_url: None,
+///     permalink: None,
+///     links: Vec::new(),
+///     title_html: None,
+///     body: None,
+/// };
+///
+/// let mut state = SeenState::default();
+/// assert_eq!(state.unseen([&article]).len(), 1);
+///
+/// state.mark_seen([&article]);
+/// assert!(state.unseen([&article]).is_empty());
+/// ```
+///
+/// Saved as JSON:
+///
+/// ```json
+/// { "seen": { "11302022a": "2022-11-30" } }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeenState {
+    /// Dates of the articles seen, by [`Article::key`].
+    pub seen: BTreeMap<String, NaiveDate>,
+}
+
+impl SeenState {
+    /// Returns the default state file path, `$XDG_DATA_HOME/apple_releases/state.json` on Linux.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("state.json"))
+    }
+
+    /// Reads the state file at `path`. A missing file is an empty state, so every article is new.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The state file.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|error| Error::Serialization(format!("{}: {}", path.display(), error))),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Writes the state to `path`, replacing it atomically so an interrupted run cannot corrupt
+    /// it.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The state file. Its directory is created if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|error| Error::Serialization(error.to_string()))?;
+        crate::write_atomic(path, &json)
+    }
+
+    /// Returns the articles which have not been seen, in order. Articles recorded by their date and
+    /// title, as older versions did, count as seen.
+    ///
+    /// # Arguments
+    ///
+    /// - `articles` - The articles to check.
+    pub fn unseen<'a>(&self, articles: impl IntoIterator<Item = &'a Article>) -> Vec<&'a Article> {
+        articles
+            .into_iter()
+            .filter(|article| {
+                !self.seen.contains_key(&article.key())
+                    && !self.seen.contains_key(&article.legacy_key())
+            })
+            .collect()
+    }
+
+    /// Records `articles` as seen, forgetting articles more than a year older than the newest.
+    /// Entries under an article's date and title key are replaced by its current key.
+    ///
+    /// # Arguments
+    ///
+    /// - `articles` - The articles to record.
+    pub fn mark_seen<'a>(&mut self, articles: impl IntoIterator<Item = &'a Article>) {
+        for article in articles {
+            let key = article.key();
+            if key != article.legacy_key() {
+                self.seen.remove(&article.legacy_key());
+            }
+            self.seen.insert(key, article.date);
+        }
+
+        if let Some(cutoff) = self
+            .seen
+            .values()
+            .max()
+            .and_then(|newest| newest.checked_sub_days(Days::new(RETENTION_DAYS)))
+        {
+            self.seen.retain(|_, date| *date >= cutoff);
+        }
+    }
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn test_article(title: &str, date: (i32, u32, u32)) -> Article {
+    Article {
+        title: title.to_string(),
+        software_release: None,
+        date: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+        release_notes_url: None,
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    }
+}
+
+#[test]
+fn test_unseen() {
+    let old = test_article("iOS 16.1.2 (20B110)", (2022, 11, 30));
+    let new = test_article("iOS 16.2 (20C65)", (2022, 12, 13));
+
+    let mut state = SeenState::default();
+    state.mark_seen([&old]);
+    assert_eq!(state.unseen([&new, &old]), vec![&new]);
+
+    state.mark_seen([&new, &old]);
+    assert!(state.unseen([&new, &old]).is_empty());
+}
+
+#[test]
+fn test_mark_seen_forgets_old_articles() {
+    let ancient = test_article("iOS 15.2.1 (19C63)", (2022, 1, 12));
+    let old = test_article("iOS 16.2 (20C65)", (2022, 12, 13));
+    let new = test_article("iOS 17.0 (21A329)", (2023, 9, 18));
+
+    let mut state = SeenState::default();
+    state.mark_seen([&ancient, &old]);
+    assert_eq!(state.seen.len(), 2);
+
+    state.mark_seen([&new]);
+    let keys: Vec<&String> = state.seen.keys().collect();
+    assert_eq!(keys, vec![&old.key(), &new.key()]);
+}
+
+#[test]
+fn test_state_file() {
+    let dir = std::env::temp_dir().join(format!("apple_releases-state-{}", std::process::id()));
+    let path = dir.join("state.json");
+
+    assert_eq!(SeenState::load(&path).unwrap(), SeenState::default());
+
+    let mut state = SeenState::default();
+    state.mark_seen([&test_article("iOS 16.2 (20C65)", (2022, 12, 13))]);
+    state.save(&path).unwrap();
+    let loaded = SeenState::load(&path);
+
+    std::fs::write(&path, "not json").unwrap();
+    let corrupt = SeenState::load(&path);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(loaded.unwrap(), state);
+    assert!(matches!(corrupt, Err(Error::Serialization(_))));
+}
+
+#[test]
+fn test_legacy_state() {
+    // Saved before articles from the page were keyed by their permalink id
+    let state: SeenState =
+        serde_json::from_str(r#"{ "seen": { "2022-12-13 iOS 16.2 (20C65)": "2022-12-13" } }"#)
+            .unwrap();
+
+    let mut article = test_article("iOS 16.2 (20C65)", (2022, 12, 13));
+    article.permalink =
+        url::Url::parse("https://developer.apple.com/news/releases/?id=12132022a").ok();
+    let new = test_article("iOS 16.3 (20D47)", (2023, 1, 23));
+    assert_eq!(state.unseen([&article, &new]), vec![&new]);
+
+    let mut state = state;
+    state.mark_seen([&article]);
+    let keys: Vec<&String> = state.seen.keys().collect();
+    assert_eq!(keys, vec!["12132022a"]);
+    assert!(state.unseen([&article]).is_empty());
+}
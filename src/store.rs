@@ -0,0 +1,320 @@
+//!
+//! store.rs
+//!
+//! Available with the `sqlite` feature.
+//!
+
+use crate::article::Article;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::{Path, PathBuf};
+
+/// Schema of the store. Each article is kept as JSON so that fields added later round-trip, with
+/// the columns most useful for ad-hoc queries alongside.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS articles (
+        key TEXT PRIMARY KEY NOT NULL,
+        date TEXT NOT NULL,
+        title TEXT NOT NULL,
+        product TEXT,
+        version TEXT,
+        article TEXT NOT NULL,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS articles_date ON articles (date);
+";
+
+/// An article from the store with the times it was seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredArticle {
+    /// The article as last seen.
+    pub article: Article,
+
+    /// When the article was first stored.
+    pub first_seen: DateTime<Utc>,
+
+    /// When the article was most recently stored.
+    pub last_seen: DateTime<Utc>,
+}
+
+/// A SQLite database of every article seen over time.
+///
+/// Apple removes old articles from the releases page; storing each fetch keeps them. Articles are
+/// keyed by [`Article::key`], so seeing an article again updates it rather than adding a copy.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::store::Store;
+/// use apple_releases::{Article, SoftwareRelease};
+/// use chrono::{NaiveDate, Utc};
+///
+/// let title = "iOS 16.1.2 (20B110)".to_string();
+/// let article = Article {
+///     software_release: SoftwareRelease::software_release(title.clone()),
+///     title,
+///     date: NaiveDate::from_ymd_opt(2022, 11, 30).unwrap(),
+///     release_notes_url: None,
+///     permalink: None,
+///     links: Vec::new(),
+///     title_html: None,
+///     body: None,
+/// };
+///
+/// let mut store = Store::open_in_memory().unwrap();
+/// assert_eq!(store.upsert(&[article.clone()], Utc::now()).unwrap(), 1);
+/// assert_eq!(store.upsert(&[article.clone()], Utc::now()).unwrap(), 0);
+/// assert_eq!(store.articles().unwrap(), vec![article]);
+/// ```
+#[derive(Debug)]
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Opens or creates the database at `path`, creating its directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The database file.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Creates a database which lives only as long as the store.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    /// Returns the default database path, `$XDG_DATA_HOME/apple_releases/releases.sqlite` on Linux.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("releases.sqlite"))
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Inserts new articles and updates existing ones in a single transaction.
+    ///
+    /// # Arguments
+    ///
+    /// - `articles` - The articles to store.
+    /// - `seen_at` - When the articles were fetched. Sets `last_seen`, and `first_seen` for new
+    ///   articles.
+    ///
+    /// # Returns
+    ///
+    /// The number of articles which were not already stored.
+    pub fn upsert(&mut self, articles: &[Article], seen_at: DateTime<Utc>) -> Result<usize> {
+        let seen_at = seen_at.to_rfc3339();
+        let transaction = self.connection.transaction()?;
+        let mut inserted = 0;
+        {
+            // Rows stored by older versions under the date and title take the current key
+            let mut migrate = transaction.prepare(
+                "UPDATE articles SET key = ?1 WHERE key = ?2
+                    AND NOT EXISTS (SELECT 1 FROM articles WHERE key = ?1)",
+            )?;
+            let mut exists = transaction.prepare("SELECT 1 FROM articles WHERE key = ?1")?;
+            let mut upsert = transaction.prepare(
+                "INSERT INTO articles
+                    (key, date, title, product, version, article, first_seen, last_seen)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+                ON CONFLICT (key) DO UPDATE SET
+                    date = excluded.date,
+                    title = excluded.title,
+                    product = excluded.product,
+                    version = excluded.version,
+                    article = excluded.article,
+                    last_seen = excluded.last_seen",
+            )?;
+
+            for article in articles {
+                let key = article.key();
+                let release = article.software_release.as_ref();
+                let json = serde_json::to_string(article)
+                    .map_err(|error| Error::Serialization(error.to_string()))?;
+
+                if key != article.legacy_key() {
+                    migrate.execute(params![key, article.legacy_key()])?;
+                }
+                if exists.query_row([&key], |_| Ok(())).optional()?.is_none() {
+                    inserted += 1;
+                }
+                upsert.execute(params![
+                    key,
+                    article.date.to_string(),
+                    article.title,
+                    release.map(|release| release.product.to_string()),
+                    release.map(|release| release.semver().to_string()),
+                    json,
+                    seen_at,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(inserted)
+    }
+
+    /// Returns every stored article, newest first.
+    pub fn articles(&self) -> Result<Vec<Article>> {
+        Ok(self
+            .records()?
+            .into_iter()
+            .map(|record| record.article)
+            .collect())
+    }
+
+    /// Returns every stored article with the times it was seen, newest first. Articles from the
+    /// same day are in the order they were first stored.
+    pub fn records(&self) -> Result<Vec<StoredArticle>> {
+        let mut statement = self.connection.prepare(
+            "SELECT article, first_seen, last_seen FROM articles ORDER BY date DESC, rowid ASC",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (json, first_seen, last_seen) = row?;
+            records.push(StoredArticle {
+                article: serde_json::from_str(&json)
+                    .map_err(|error| Error::Serialization(error.to_string()))?,
+                first_seen: parse_timestamp(&first_seen)?,
+                last_seen: parse_timestamp(&last_seen)?,
+            });
+        }
+        Ok(records)
+    }
+}
+
+/// Parses a stored RFC 3339 timestamp.
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|source| Error::Date {
+            value: timestamp.to_string(),
+            source,
+        })
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn test_article(title: &str, date: (i32, u32, u32)) -> Article {
+    use crate::software_release::SoftwareRelease;
+    use chrono::NaiveDate;
+
+    Article {
+        title: title.to_string(),
+        software_release: SoftwareRelease::software_release(title.to_string()),
+        date: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+        release_notes_url: None,
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    }
+}
+
+#[test]
+fn test_store_upsert() {
+    use chrono::TimeZone;
+
+    let first = Utc.with_ymd_and_hms(2022, 11, 30, 12, 0, 0).unwrap();
+    let second = Utc.with_ymd_and_hms(2022, 12, 14, 12, 0, 0).unwrap();
+
+    let mut store = Store::open_in_memory().unwrap();
+    let old = test_article("iOS 16.1.2 (20B110)", (2022, 11, 30));
+    let beta = test_article("iOS 16.2 beta 4 (20C5058d)", (2022, 11, 30));
+    assert_eq!(
+        store.upsert(&[old.clone(), beta.clone()], first).unwrap(),
+        2
+    );
+
+    // The old article has rotated off the page
+    let new = test_article("iOS 16.2 (20C65)", (2022, 12, 13));
+    assert_eq!(
+        store.upsert(&[new.clone(), beta.clone()], second).unwrap(),
+        1
+    );
+
+    let records = store.records().unwrap();
+    let articles: Vec<&Article> = records.iter().map(|record| &record.article).collect();
+    assert_eq!(articles, vec![&new, &old, &beta]);
+
+    assert_eq!(records[0].first_seen, second);
+    assert_eq!(records[1].first_seen, first);
+    assert_eq!(records[1].last_seen, first);
+    assert_eq!(records[2].first_seen, first);
+    assert_eq!(records[2].last_seen, second);
+}
+
+#[test]
+fn test_store_updates_article() {
+    use url::Url;
+
+    let mut store = Store::open_in_memory().unwrap();
+    let mut article = test_article("Xcode 14.1 (14B47b)", (2022, 11, 1));
+    store.upsert(&[article.clone()], Utc::now()).unwrap();
+
+    article.release_notes_url = Url::parse("https://developer.apple.com/go/?id=xcode-14.1-rn").ok();
+    assert_eq!(store.upsert(&[article.clone()], Utc::now()).unwrap(), 0);
+    assert_eq!(store.articles().unwrap(), vec![article]);
+}
+
+#[test]
+fn test_store_legacy_key() {
+    use chrono::TimeZone;
+    use url::Url;
+
+    let first = Utc.with_ymd_and_hms(2022, 12, 13, 12, 0, 0).unwrap();
+    let second = Utc.with_ymd_and_hms(2022, 12, 14, 12, 0, 0).unwrap();
+
+    // Stored before articles from the page were keyed by their permalink id
+    let mut store = Store::open_in_memory().unwrap();
+    let mut article = test_article("iOS 16.2 (20C65)", (2022, 12, 13));
+    store.upsert(std::slice::from_ref(&article), first).unwrap();
+
+    article.permalink = Url::parse("https://developer.apple.com/news/releases/?id=12132022a").ok();
+    assert_eq!(
+        store
+            .upsert(std::slice::from_ref(&article), second)
+            .unwrap(),
+        0
+    );
+
+    let records = store.records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].article, article);
+    assert_eq!(records[0].first_seen, first);
+    assert_eq!(records[0].last_seen, second);
+}
+
+#[test]
+fn test_store_file() {
+    let dir = std::env::temp_dir().join(format!("apple_releases-store-{}", std::process::id()));
+    let path = dir.join("releases.sqlite");
+    let article = test_article("iOS 16.1.2 (20B110)", (2022, 11, 30));
+
+    Store::open(&path)
+        .unwrap()
+        .upsert(std::slice::from_ref(&article), Utc::now())
+        .unwrap();
+    let articles = Store::open(&path).unwrap().articles().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(articles, vec![article]);
+}
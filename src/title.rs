@@ -0,0 +1,331 @@
+//!
+//! title.rs
+//!
+
+use crate::error::{Error, Result};
+use crate::product::Product;
+use crate::release_stage::ReleaseStage;
+
+/// Longest product name, in words, tried when matching the start of a title.
+const MAX_PRODUCT_WORDS: usize = 4;
+
+/// The parts of a software release title.
+///
+/// Titles follow the grammar:
+///
+/// ```text
+/// title      := product [marketing] version [stage] { "(" group ")" }
+/// product    := one or more words naming a known `Product`, or up to four capitalized words
+///               directly before the version, which become `Product::Other` if the title also
+///               has a build number or a beta or RC stage
+/// marketing  := one or more words without digits, e.g. "Sonoma" or "Big Sur"
+/// version    := digits { "." digits }
+/// stage      := words such as "beta 3", "RC 2", "Public Beta" or "Update"
+/// group      := a build number such as "20C5049e", or other text such as "a"
+/// ```
+#[derive(Debug, PartialEq)]
+pub(crate) struct Title<'a> {
+    /// The product named at the start of the title.
+    pub(crate) product: Product,
+
+    /// Marketing name between the product and version, e.g. `Sonoma`.
+    pub(crate) marketing_name: Option<String>,
+
+    /// Numeric version components. Missing components are zero.
+    pub(crate) version: (u64, u64, u64),
+
+    /// Words after the version, including parenthesized text which is not a build number.
+    pub(crate) stage_words: Vec<&'a str>,
+
+    /// Text of the first parenthesized group which looks like a build number.
+    pub(crate) build: Option<&'a str>,
+}
+
+/// Parses a release title.
+///
+/// # Returns
+///
+/// - `Ok(Some(title))` if the title starts with a product and contains a version.
+/// - `Ok(None)` if the title does not start with a product, or names a product without a version
+///   or build number, as announcements do.
+/// - `Err(Error::Version)` if the title starts with a known product but its version is malformed,
+///   or missing before a build number.
+pub(crate) fn parse_title(title: &str) -> Result<Option<Title<'_>>> {
+    let invalid = |message: &str| Error::Version {
+        value: title.to_string(),
+        message: message.to_string(),
+    };
+
+    // Everything before the first parenthesis is words; everything after is groups.
+    let (head, groups) = match title.find('(') {
+        Some(index) => title.split_at(index),
+        None => (title, ""),
+    };
+    let words: Vec<&str> = head.split_whitespace().collect();
+
+    let Some((product, product_words)) =
+        match_product(&words).or_else(|| match_other_product(&words))
+    else {
+        return Ok(None);
+    };
+    let rest = &words[product_words..];
+
+    let Some(version_index) = rest
+        .iter()
+        .position(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+    else {
+        // Announcements such as "Xcode Cloud now available" name a product without a version,
+        // but a build number means a release whose version could not be read
+        let has_build = groups
+            .split('(')
+            .any(|group| looks_like_build(group_text(group)));
+        return if has_build {
+            Err(invalid("missing version"))
+        } else {
+            Ok(None)
+        };
+    };
+
+    let marketing = &rest[..version_index];
+    if marketing
+        .iter()
+        .any(|word| !word.chars().all(char::is_alphabetic))
+    {
+        return Err(invalid("unexpected words before version"));
+    }
+    let marketing_name = (!marketing.is_empty()).then(|| marketing.join(" "));
+
+    let version = parse_version(rest[version_index]).ok_or_else(|| invalid("invalid version"))?;
+    let mut stage_words = rest[version_index + 1..].to_vec();
+
+    let mut build = None;
+    for group in groups.split('(').map(group_text) {
+        if group.is_empty() {
+            continue;
+        }
+        if build.is_none() && looks_like_build(group) {
+            build = Some(group);
+        } else {
+            stage_words.push(group);
+        }
+    }
+
+    // Unknown names followed by a number, such as "WWDC 2024", are only releases if they also
+    // look like one
+    if matches!(product, Product::Other(_))
+        && build.is_none()
+        && !matches!(
+            ReleaseStage::from_words(&stage_words),
+            ReleaseStage::DeveloperBeta(_)
+                | ReleaseStage::PublicBeta(_)
+                | ReleaseStage::ReleaseCandidate(_)
+        )
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(Title {
+        product,
+        marketing_name,
+        version,
+        stage_words,
+        build,
+    }))
+}
+
+/// Matches the longest run of leading words which is the display name of a known product. The
+/// aliases accepted by `--product`, such as `mac`, are not matched.
+///
+/// # Returns
+///
+/// The product and the number of words it spans.
+fn match_product(words: &[&str]) -> Option<(Product, usize)> {
+    (1..=words.len().min(MAX_PRODUCT_WORDS))
+        .rev()
+        .find_map(|count| {
+            Product::from_display_name(&words[..count].join(" ")).map(|product| (product, count))
+        })
+}
+
+/// Matches capitalized words directly followed by a version as an unknown product, e.g.
+/// `Final Cut Pro 10.7`.
+///
+/// # Returns
+///
+/// The product and the number of words it spans.
+fn match_other_product(words: &[&str]) -> Option<(Product, usize)> {
+    let count = words
+        .iter()
+        .take(MAX_PRODUCT_WORDS + 1)
+        .position(|word| parse_version(word).is_some())?;
+
+    let is_name = |word: &&str| {
+        let mut chars = word.chars();
+        match chars.next() {
+            // Names like "Safari" or "iMovie"
+            Some(first) if first.is_uppercase() => true,
+            Some(first) if first.is_lowercase() => chars.any(char::is_uppercase),
+            _ => false,
+        }
+    };
+
+    (count > 0 && words[..count].iter().all(is_name))
+        .then(|| (Product::Other(words[..count].join(" ")), count))
+}
+
+/// Returns the text of a parenthesized group, without its closing parenthesis.
+fn group_text(group: &str) -> &str {
+    group.trim_end().trim_end_matches(')').trim()
+}
+
+/// Parses a version such as `16`, `16.2` or `16.1.2`.
+fn parse_version(word: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = word.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// Returns `true` for text shaped like a build number: digits, a capital letter, then more
+/// alphanumerics, e.g. `20C5049e` or `20E772520a`.
+fn looks_like_build(text: &str) -> bool {
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    let mut rest = text[digits..].chars();
+    digits > 0
+        && rest.next().is_some_and(|c| c.is_ascii_uppercase())
+        && rest.as_str().chars().all(|c| c.is_ascii_alphanumeric())
+        && !rest.as_str().is_empty()
+}
+
+/// Cleans up title text: runs of whitespace, including non-breaking spaces, become single spaces,
+/// and zero-width characters are removed.
+pub(crate) fn normalize_title(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| word.replace(['\u{200b}', '\u{200c}', '\u{200d}', '\u{feff}'], ""))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the text of a title which may contain HTML markup or entities, normalized.
+pub(crate) fn title_text(html: &str) -> String {
+    if !html.contains(['<', '&']) {
+        return normalize_title(html);
+    }
+    let fragment = scraper::Html::parse_fragment(html);
+    normalize_title(&fragment.root_element().text().collect::<String>())
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[test]
+fn test_parse_title_simple() {
+    let title = parse_title("iOS 16.2 beta 3 (20C5049e)").unwrap().unwrap();
+    assert_eq!(
+        title,
+        Title {
+            product: Product::iOS,
+            marketing_name: None,
+            version: (16, 2, 0),
+            stage_words: vec!["beta", "3"],
+            build: Some("20C5049e"),
+        }
+    );
+}
+
+#[test]
+fn test_parse_title_marketing_name() {
+    let title = parse_title("macOS Big Sur 11.2 RC 2 (20D62)")
+        .unwrap()
+        .unwrap();
+    assert_eq!(title.marketing_name.as_deref(), Some("Big Sur"));
+    assert_eq!(title.version, (11, 2, 0));
+    assert_eq!(title.stage_words, vec!["RC", "2"]);
+    assert_eq!(title.build, Some("20D62"));
+}
+
+#[test]
+fn test_parse_title_extra_groups() {
+    let title = parse_title("iOS 16.4.1 (a) (20E772520a)").unwrap().unwrap();
+    assert_eq!(title.version, (16, 4, 1));
+    assert_eq!(title.stage_words, vec!["a"]);
+    assert_eq!(title.build, Some("20E772520a"));
+
+    // Missing closing parenthesis
+    let title = parse_title("iOS 16.2 (20C65").unwrap().unwrap();
+    assert_eq!(title.build, Some("20C65"));
+}
+
+#[test]
+fn test_parse_title_errors() {
+    assert_eq!(
+        parse_title("Upcoming requirements for app distribution").unwrap(),
+        None
+    );
+    assert_eq!(parse_title("Updated agreements for 2024").unwrap(), None);
+    assert_eq!(parse_title("").unwrap(), None);
+    assert_eq!(parse_title("iOS").unwrap(), None);
+    assert_eq!(parse_title("App Store Connect & TestFlight").unwrap(), None);
+    assert_eq!(parse_title("Xcode Cloud now available").unwrap(), None);
+    assert!(parse_title("iOS sixteen (20B110)").is_err());
+    assert!(parse_title("iOS 16.x").is_err());
+    assert!(parse_title("iOS 1.2.3.4").is_err());
+}
+
+#[test]
+fn test_parse_title_multi_word_product() {
+    let title = parse_title("App Store Connect 1.11").unwrap().unwrap();
+    assert_eq!(title.product, Product::AppStoreConnect);
+    assert_eq!(title.version, (1, 11, 0));
+
+    let title = parse_title("Xcode Cloud 2 beta").unwrap().unwrap();
+    assert_eq!(title.product, Product::XcodeCloud);
+    assert_eq!(title.stage_words, vec!["beta"]);
+}
+
+#[test]
+fn test_parse_title_other_product() {
+    let title = parse_title("Final Cut Pro 10.7 (23H420)").unwrap().unwrap();
+    assert_eq!(title.product, Product::Other("Final Cut Pro".to_string()));
+    assert_eq!(title.version, (10, 7, 0));
+
+    let title = parse_title("iMovie 3.0 beta 2").unwrap().unwrap();
+    assert_eq!(title.product, Product::Other("iMovie".to_string()));
+
+    // Without a build or stage, unknown names followed by a number are not releases
+    assert_eq!(parse_title("WWDC 2024").unwrap(), None);
+    assert_eq!(parse_title("Apple Design Awards 2024").unwrap(), None);
+    assert_eq!(parse_title("Final Cut Pro 10.7 (420)").unwrap(), None);
+
+    // Aliases of known products do not start a title
+    assert_eq!(parse_title("Watch Party 2024").unwrap(), None);
+    assert_eq!(parse_title("Mac Pro 2023 now available").unwrap(), None);
+    let title = parse_title("Mac Pro 2 beta").unwrap().unwrap();
+    assert_eq!(title.product, Product::Other("Mac Pro".to_string()));
+}
+
+#[test]
+fn test_normalize_title() {
+    assert_eq!(
+        normalize_title("  Xcode\u{a0}15\u{200b} beta\n  (15A5160n) "),
+        "Xcode 15 beta (15A5160n)"
+    );
+    assert_eq!(normalize_title("\u{a0}"), "");
+}
+
+#[test]
+fn test_title_text() {
+    assert_eq!(
+        title_text("Safari&nbsp;17 <span>beta</span>"),
+        "Safari 17 beta"
+    );
+    assert_eq!(
+        title_text("App Store Connect &amp; TestFlight"),
+        "App Store Connect & TestFlight"
+    );
+    assert_eq!(title_text("iOS 17.2 (21C62)"), "iOS 17.2 (21C62)");
+}
@@ -4,20 +4,26 @@
 
 use url::Url;
 
-use crate::{GenericResult, APPLE_DEV_RELEASES, APP_USER_AGENT};
+use crate::RELEASES_PAGE;
+use crate::error::{Error, Result};
+use crate::fetcher::{Fetcher, HttpFetcher};
 
 /// Gets a URL and returns the body of the response.
 ///
 /// # Arguments
 ///
 /// - `url` - The URL to get.
-pub fn get(url: String) -> GenericResult<String> {
-    let client = reqwest::blocking::Client::builder()
-        .user_agent(APP_USER_AGENT)
-        .build()?;
-    let res = client.get(url).send()?;
-    let body = res.text()?;
-    Ok(body)
+///
+/// # Example
+///
+/// ```no_run
+/// use apple_releases::APPLE_DEV_RELEASES;
+///
+/// let body = apple_releases::url::get(APPLE_DEV_RELEASES.to_string()).unwrap();
+/// assert!(body.contains("article-content-container"));
+/// ```
+pub fn get(url: String) -> Result<String> {
+    HttpFetcher::new()?.fetch(&url)
 }
 
 /// Builds the release notes URL.
@@ -25,16 +31,47 @@ pub fn get(url: String) -> GenericResult<String> {
 /// # Arguments
 ///
 /// - `notes` - The release notes path.
-pub(crate) fn build_notes_url(notes_path: Option<String>) -> Option<Url> {
-    notes_path.map(|path| {
-        let base_url = Url::parse(APPLE_DEV_RELEASES).unwrap();
-        base_url.join(&path).unwrap()
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::url::build_notes_url;
+///
+/// let url = build_notes_url(Some("/go/?id=xcode-14-sdk-rn".to_string())).unwrap();
+/// assert_eq!(
+///     url.unwrap().as_str(),
+///     "https://developer.apple.com/go/?id=xcode-14-sdk-rn"
+/// );
+/// ```
+pub fn build_notes_url(notes_path: Option<String>) -> Result<Option<Url>> {
+    notes_path
+        .map(|path| resolve(&RELEASES_PAGE, &path))
+        .transpose()
+}
+
+/// Parses the URL of a page which is about to be read, e.g. from `--base-url`.
+pub(crate) fn page_url(url: &str) -> Result<Url> {
+    Url::parse(url).map_err(|source| Error::Url {
+        value: url.to_string(),
+        source,
+    })
+}
+
+/// Resolves `path` against `base`, the URL of the page it was found on.
+pub(crate) fn resolve(base: &Url, path: &str) -> Result<Url> {
+    base.join(path).map_err(|source| Error::Url {
+        value: path.to_string(),
+        source,
     })
 }
 
 /* ---------------------------------------------------------------------------------------------- */
 
+#[cfg(test)]
+use crate::APPLE_DEV_RELEASES;
+
 #[test]
+#[ignore = "requires network access to developer.apple.com"]
 fn test_get() {
     let body = get(APPLE_DEV_RELEASES.to_string()).unwrap();
     assert!(!body.is_empty());
@@ -44,13 +81,35 @@ fn test_get() {
 fn test_build_notes_url() {
     let expected_url = Url::parse("https://developer.apple.com/go/?id=xcode-14-sdk-rn").unwrap();
     let path = Some("/go/?id=xcode-14-sdk-rn".to_string());
-    let url = build_notes_url(path).unwrap();
+    let url = build_notes_url(path).unwrap().unwrap();
     assert_eq!(url, expected_url);
 }
 
 #[test]
 fn test_build_notes_url_with_none() {
     let expected_url = None;
-    let url = build_notes_url(None);
+    let url = build_notes_url(None).unwrap();
     assert_eq!(url, expected_url);
 }
+
+#[test]
+fn test_resolve() {
+    let base = Url::parse("http://localhost:8080/releases/").unwrap();
+    assert_eq!(
+        resolve(&base, "/go/?id=ios-16.2-rn").unwrap().as_str(),
+        "http://localhost:8080/go/?id=ios-16.2-rn"
+    );
+    assert_eq!(
+        resolve(&base, "https://developer.apple.com/download/")
+            .unwrap()
+            .as_str(),
+        "https://developer.apple.com/download/"
+    );
+}
+
+#[test]
+fn test_build_notes_url_invalid() {
+    let path = Some("https://[::1".to_string());
+    let result = build_notes_url(path);
+    assert!(matches!(result, Err(Error::Url { .. })));
+}
@@ -0,0 +1,227 @@
+//!
+//! watch.rs
+//!
+
+use crate::article::Article;
+use crate::error::Result;
+use crate::output::ArticleRecord;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::process::{Command, ExitStatus};
+use std::time::Duration;
+
+/// Something noticed while watching for releases, printed by `apple_releases watch` as one line of
+/// JSON.
+///
+/// # JSON
+///
+/// ```json
+/// {
+///   "event": "new_release",
+///   "seen_at": "2022-12-13T18:00:00Z",
+///   "article": { "title": "iOS 16.2 (20C65)", ... }
+/// }
+/// ```
+///
+/// See [`Article`] for the shape of `article`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    /// An article which had not been seen before.
+    NewRelease {
+        /// When the article was first seen.
+        seen_at: DateTime<Utc>,
+
+        /// The new article.
+        article: Article,
+    },
+}
+
+/// Fills in the placeholders of a hook command for `article`.
+///
+/// Placeholders are the column names of the CSV output in braces: `{date}`, `{title}`,
+/// `{product}`, `{version}`, `{major}`, `{minor}`, `{patch}`, `{prerelease}`, `{build}`,
+/// `{stage}`, `{release_notes_url}`, `{permalink}`, `{id}`, `{marketing_name}`, `{title_html}`,
+/// `{links}` and `{body}`. Each is replaced by its value quoted for `sh`, or by `''` if the
+/// article has no such value. Other text, including unknown placeholders, is left alone.
+///
+/// # Arguments
+///
+/// - `template` - The command, e.g. `notify {product} {version}`.
+/// - `article` - The article to describe.
+///
+/// # Example
+///
+/// ```
+/// use apple_releases::watch::hook_command;
+/// use apple_releases::{Article, SoftwareRelease};
+/// use chrono::NaiveDate;
+///
+/// let title = "iOS 16.1.2 (20B110)".to_string();
+/// let article = Article {
+///     software_release: SoftwareRelease::software_release(title.clone()),
+///     title,
+///     date: NaiveDate::from_ymd_opt(2022, 11, 30).unwrap(),
+///     release_notes_url: None,
+///     permalink: None,
+///     links: Vec::new(),
+///     title_html: None,
+///     body: None,
+/// };
+///
+/// assert_eq!(
+///     hook_command("notify {product} {title} {release_notes_url}", &article),
+///     "notify 'iOS' 'iOS 16.1.2 (20B110)' ''"
+/// );
+/// ```
+pub fn hook_command(template: &str, article: &Article) -> String {
+    let record = ArticleRecord::new(article);
+    let cells = record.cells();
+    let value = |name: &str| {
+        ArticleRecord::HEADERS
+            .iter()
+            .position(|header| *header == name)
+            .map(|index| &cells[index])
+    };
+
+    // Scan the template once, so text from a substituted value is never expanded again
+    let mut command = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        command.push_str(&rest[..open]);
+        let placeholder = &rest[open..];
+        match placeholder
+            .find('}')
+            .and_then(|close| value(&placeholder[1..close]).map(|value| (close, value)))
+        {
+            Some((close, value)) => {
+                command.push_str(&shell_quote(value));
+                rest = &placeholder[close + 1..];
+            }
+            None => {
+                command.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    command.push_str(rest);
+    command
+}
+
+/// Runs a hook command for `article` with `sh -c`, waiting for it to finish.
+///
+/// # Arguments
+///
+/// - `template` - The command, with placeholders as described for [`hook_command`].
+/// - `article` - The article to describe.
+///
+/// # Returns
+///
+/// The exit status of the command, or an error if it could not be started.
+pub fn run_hook(template: &str, article: &Article) -> Result<ExitStatus> {
+    Ok(Command::new("sh")
+        .arg("-c")
+        .arg(hook_command(template, article))
+        .status()?)
+}
+
+/// Returns `interval` lengthened or shortened by a random amount of up to a tenth, so that many
+/// watchers started together do not poll in lockstep.
+pub fn jittered(interval: Duration) -> Duration {
+    interval.mul_f64(0.9 + crate::fetcher::random_fraction() / 5.0)
+}
+
+/// Quotes `value` as a single `sh` word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+
+#[cfg(test)]
+fn test_article(title: &str) -> Article {
+    use crate::software_release::SoftwareRelease;
+    use chrono::NaiveDate;
+
+    Article {
+        title: title.to_string(),
+        software_release: SoftwareRelease::software_release(title.to_string()),
+        date: NaiveDate::from_ymd_opt(2022, 11, 15).unwrap(),
+        release_notes_url: url::Url::parse("https://developer.apple.com/go/?id=ios-16.2-rn").ok(),
+        permalink: None,
+        links: Vec::new(),
+        title_html: None,
+        body: None,
+    }
+}
+
+#[test]
+fn test_hook_command() {
+    let article = test_article("iOS 16.2 beta 3 (20C5049e)");
+    assert_eq!(
+        hook_command("notify {product} {version} {stage} {build}", &article),
+        "notify 'iOS' '16.2.0-beta-3+20C5049e' 'beta 3' '20C5049e'"
+    );
+    assert_eq!(
+        hook_command(
+            "open {release_notes_url} && echo ${HOME} {unknown}",
+            &article
+        ),
+        "open 'https://developer.apple.com/go/?id=ios-16.2-rn' && echo ${HOME} {unknown}"
+    );
+
+    // Placeholders in a value are not expanded again
+    let article = test_article("iOS {release_notes_url} {{title}}");
+    assert_eq!(
+        hook_command("echo {title} {release_notes_url}", &article),
+        "echo 'iOS {release_notes_url} {{title}}' 'https://developer.apple.com/go/?id=ios-16.2-rn'"
+    );
+
+    let article = test_article("Apple's new requirements");
+    assert_eq!(
+        hook_command("echo {title} {product}", &article),
+        r"echo 'Apple'\''s new requirements' ''"
+    );
+}
+
+#[test]
+fn test_run_hook() {
+    let article = test_article("iOS 16.2 (20C65)");
+    assert!(
+        run_hook("test {product} = iOS", &article)
+            .unwrap()
+            .success()
+    );
+    assert!(
+        !run_hook("test {product} = macOS", &article)
+            .unwrap()
+            .success()
+    );
+}
+
+#[test]
+fn test_watch_event_json() {
+    use chrono::TimeZone;
+
+    let event = WatchEvent::NewRelease {
+        seen_at: Utc.with_ymd_and_hms(2022, 11, 15, 18, 0, 0).unwrap(),
+        article: test_article("iOS 16.2 beta 3 (20C5049e)"),
+    };
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["event"], "new_release");
+    assert_eq!(json["seen_at"], "2022-11-15T18:00:00Z");
+    assert_eq!(json["article"]["title"], "iOS 16.2 beta 3 (20C5049e)");
+}
+
+#[test]
+fn test_jittered() {
+    let interval = Duration::from_secs(900);
+    for _ in 0..10 {
+        let delay = jittered(interval);
+        assert!(
+            delay >= Duration::from_secs(810) && delay <= Duration::from_secs(990),
+            "{:?}",
+            delay
+        );
+    }
+}